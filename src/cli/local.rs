@@ -0,0 +1,200 @@
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cli::{CliError, Command, WaterArgs};
+use crate::config::Config;
+use crate::services::camera_service::{CameraService, Resolution};
+use crate::services::climate_poller::ClimatePoller;
+use crate::services::plants::{Plants, WateringOutcome};
+use crate::services::safety::Actuator;
+use crate::services::soil::Soil;
+use crate::services::usage::Usage;
+use crate::services::water::Water;
+use crate::utils::camera::Camera;
+use crate::utils::storage::Storage;
+
+/// Runs the command on hardware of this Pi, without the server.
+pub async fn run(config: &Config, command: &Command) -> Result<(), CliError> {
+    match command {
+        Command::Water(args) => water(config, args).await,
+        Command::Photo { out } => photo(config, out).await,
+        Command::ClimateShow => climate_show(config).await,
+        Command::Switch { .. } => Err(CliError::Usage("switches are kept by the server, use --remote".to_owned())),
+        other => Err(CliError::Usage(format!("{:?} is not a local command", other)))
+    }
+}
+
+/// Checks values and references between config sections, hardware is not touched.
+pub fn check_config(config: &Config) -> Result<(), CliError> {
+    let mut problems = Vec::new();
+
+    if let Err(e) = SocketAddr::from_str(&config.address) {
+        problems.push(format!("invalid address {}: {}", &config.address, e));
+    }
+
+    if !Path::new(&config.log_config_path).exists() {
+        problems.push(format!("log config {} does not exist", &config.log_config_path));
+    }
+
+    let has_sensor = |id: &str| config.water_sensors.iter().any(|s| s.id.eq_ignore_ascii_case(id));
+    let has_pump = |id: &str| config.water_pumps.iter().any(|p| p.id.eq_ignore_ascii_case(id));
+    let has_servo = |id: &str| config.servos.iter().any(|s| s.id.eq_ignore_ascii_case(id));
+
+    for pump in &config.water_pumps {
+        if let Some(sensor_id) = &pump.water_sensor_id {
+            if !has_sensor(sensor_id) {
+                problems.push(format!("water pump {} refers to unknown water sensor {}", &pump.id, sensor_id));
+            }
+        }
+    }
+
+    for plant in &config.plants {
+        if !has_pump(&plant.pump_id) {
+            problems.push(format!("plant {} refers to unknown water pump {}", &plant.id, &plant.pump_id));
+        }
+        if let Some(sensor_id) = &plant.water_sensor_id {
+            if !has_sensor(sensor_id) {
+                problems.push(format!("plant {} refers to unknown water sensor {}", &plant.id, sensor_id));
+            }
+        }
+        if let Some(sensor_id) = &plant.soil_sensor_id {
+            if !config.soil_sensors.iter().any(|s| s.id.eq_ignore_ascii_case(sensor_id)) {
+                problems.push(format!("plant {} refers to unknown soil sensor {}", &plant.id, sensor_id));
+            }
+        }
+    }
+
+    if !config.soil_sensors.is_empty() && config.adc.is_none() {
+        problems.push("soil sensors need adc".to_owned());
+    }
+
+    if let Some(rig) = &config.camera_rig {
+        for servo_id in [&rig.pan_servo_id, &rig.tilt_servo_id] {
+            if !has_servo(servo_id) {
+                problems.push(format!("camera rig refers to unknown servo {}", servo_id));
+            }
+        }
+    }
+
+    if let Some(timelapse) = &config.timelapse {
+        for pose in &timelapse.poses {
+            let known = config.camera_rig.as_ref().map(|r| r.poses.contains_key(pose)).unwrap_or(false);
+            if !known {
+                problems.push(format!("time-lapse pose {} is not configured in camera rig", pose));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        println!("config is valid: {} pumps, {} plants, {} climate sensors, {} servos",
+            config.water_pumps.len(), config.plants.len(), config.climate_sensors.len(), config.servos.len());
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("{}", problem);
+    }
+
+    Err(CliError::Failed(format!("config has {} problems", problems.len())))
+}
+
+async fn water(config: &Config, args: &WaterArgs) -> Result<(), CliError> {
+    let storage = Arc::new(Storage::new(&config.state_path)?);
+    let usage = Arc::new(Usage::new(&config.conditioners, &config.water_pumps, &storage)?);
+    let water = Arc::new(Water::new(&config.water_pumps, &config.water_sensors, &storage, &usage)?);
+
+    let task_water = water.clone();
+    let plant_id = args.plant_id.clone();
+    let pump_id = args.pump_id.clone();
+    let (duration, volume_ml, force) = (args.duration_seconds, args.volume_ml, args.force);
+    let (adc, soil_sensors, plants) = (config.adc.clone(), config.soil_sensors.clone(), config.plants.clone());
+
+    let task = tokio::task::spawn_blocking(move || -> Result<String, CliError> {
+        let soil = Arc::new(Soil::new(&adc, &soil_sensors)?);
+        let plants = Plants::new(&plants, &task_water, &soil, &storage)?;
+
+        if let Some(plant_id) = plant_id {
+            return Ok(match plants.water(&plant_id, volume_ml, force)? {
+                WateringOutcome::Watered(ml) => format!("plant {} watered with {}ml", plant_id, ml),
+                WateringOutcome::NotEnoughWater => "not enough water".to_owned(),
+                WateringOutcome::TooFrequent(wait) => format!("plant was watered recently, next watering in {}s", wait),
+                WateringOutcome::DailyVolumeExceeded(left) => format!("daily volume exceeded, {}ml left for today", left.max(0.0)),
+                WateringOutcome::MoistEnough(moisture) => format!("soil is moist enough: {}%", moisture)
+            });
+        }
+
+        let pump_id = pump_id.as_deref();
+        let pump_plants = plants.plants_of_pump(pump_id)?;
+        if !force && !pump_plants.is_empty() {
+            return Ok(format!("pump waters plants {}, water them with --plant or use --force", pump_plants.join(", ")));
+        }
+
+        if !force && !task_water.is_enough_for_pump(pump_id)? {
+            return Ok("not enough water".to_owned());
+        }
+
+        let duration = match volume_ml {
+            Some(volume_ml) => task_water.duration_for_volume(pump_id, volume_ml)?.duration,
+            None => Duration::from_secs(duration.unwrap_or(0))
+        };
+
+        task_water.enable_pump(pump_id, duration)?;
+        Ok(format!("pump worked for {}s", duration.as_secs_f32()))
+    });
+
+    // pump should not stay on if the command is interrupted
+    tokio::select! {
+        result = task => {
+            println!("{}", result.map_err(|e| CliError::Server(e.into()))??);
+            Ok(())
+        },
+        _ = tokio::signal::ctrl_c() => {
+            water.safe_state();
+            Err(CliError::Failed("interrupted, pumps are stopped".to_owned()))
+        }
+    }
+}
+
+async fn photo(config: &Config, out: &str) -> Result<(), CliError> {
+    let camera = if config.camera.simulated {
+        Camera::Simulated
+    } else {
+        Camera::new().map_err(|e| CliError::Server(e.into()))?
+    };
+
+    let camera = CameraService::start(&config.camera, camera)?;
+    let photo = camera.capture(Resolution::Full).await.map_err(|e| CliError::Server(e.into()))?;
+
+    fs::write(out, &photo)?;
+    println!("photo saved to {} ({} bytes)", out, photo.len());
+    Ok(())
+}
+
+async fn climate_show(config: &Config) -> Result<(), CliError> {
+    let configs = config.climate_sensors.clone();
+    let w1_devices_path = config.w1_devices_path.clone();
+
+    let readings = tokio::task::spawn_blocking(move || ClimatePoller::read_once(&configs, &w1_devices_path))
+        .await
+        .map_err(|e| CliError::Server(e.into()))??;
+
+    if readings.is_empty() {
+        println!("no climate sensors configured");
+    }
+
+    for (id, reading) in readings {
+        match reading {
+            Ok(r) => match r.humidity {
+                Some(h) => println!("{}: {}°C, {}%", id, r.temperature, h),
+                None => println!("{}: {}°C", id, r.temperature)
+            },
+            Err(e) => println!("{}: error {}", id, e)
+        }
+    }
+
+    Ok(())
+}
\ No newline at end of file
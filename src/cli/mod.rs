@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::io;
+
+use thiserror::Error;
+
+use crate::server::server_error::ServerError;
+use crate::utils::climate_sensor::ClimateSensorError;
+
+pub mod local;
+pub mod remote;
+
+const DEFAULT_CONFIG_PATH : &str = "config.json";
+/// Options without value, any other option takes the next argument.
+const FLAGS : [&str; 2] = ["force", "help"];
+
+pub const USAGE : &str = "\
+Usage: rpi_home <command> [--config <path>] [--remote <url>] [--key <key>]
+
+Commands:
+  serve                         run the server (default, `rpi_home <config>` also works)
+  check-config                  validate config without touching hardware
+  unit-file                     print recommended systemd unit for the config
+  water [--pump <id>] [--plant <id>] [--duration <seconds>] [--volume <ml>] [--force]
+                                run a pump or water a plant
+  photo --out <file.jpg>        take a photo
+  switch set <name> on|off      set a switch, needs --remote
+  climate show                  print climate readings
+  help                          print this message
+
+Options:
+  --config <path>               config file, config.json by default
+  --remote <url>                call a running server over HTTP instead of hardware,
+                                for example http://raspberrypi:8080
+  --key <key>                   protected key for --remote, taken from the config by default
+
+Local commands use hardware directly and are meant for bench testing,
+they should not be run while the server uses the same pins.
+";
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("{0}")]
+    Failed(String),
+    #[error("{0}")]
+    Server(#[from] ServerError),
+    #[error("Io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Climate sensor error: {0}")]
+    ClimateSensor(#[from] ClimateSensorError),
+    #[error("Http error: {0}")]
+    Hyper(#[from] hyper::Error),
+    #[error("Http error: {0}")]
+    Http(#[from] hyper::http::Error),
+    #[error("Server responded {0}: {1}")]
+    Remote(hyper::StatusCode, String)
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Serve,
+    CheckConfig,
+    UnitFile,
+    Water(WaterArgs),
+    Photo { out: String },
+    Switch { name: String, enabled: bool },
+    ClimateShow,
+    Help
+}
+
+#[derive(Debug)]
+pub struct WaterArgs {
+    pub pump_id: Option<String>,
+    pub plant_id: Option<String>,
+    pub duration_seconds: Option<u64>,
+    pub volume_ml: Option<f32>,
+    pub force: bool
+}
+
+#[derive(Debug)]
+pub struct Args {
+    pub command: Command,
+    pub config_path: String,
+    pub remote: Option<String>,
+    pub key: Option<String>
+}
+
+impl Args {
+    /// Parses arguments without the executable name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, CliError> {
+        let mut positionals = Vec::new();
+        let mut options = HashMap::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let name = match arg.strip_prefix("--") {
+                Some(n) => n.to_owned(),
+                None => {
+                    positionals.push(arg);
+                    continue;
+                }
+            };
+
+            let value = if FLAGS.contains(&name.as_str()) {
+                None
+            } else {
+                match args.next() {
+                    Some(v) => Some(v),
+                    None => return Err(usage(format!("option --{} needs a value", name)))
+                }
+            };
+
+            options.insert(name, value);
+        }
+
+        let mut positionals = positionals.into_iter();
+        let mut config_path = options.remove("config").flatten();
+
+        let command = match positionals.next().as_deref() {
+            None | Some("serve") => Command::Serve,
+            Some("check-config") => Command::CheckConfig,
+            Some("unit-file") => Command::UnitFile,
+            Some("water") => Command::Water(WaterArgs {
+                pump_id: options.remove("pump").flatten(),
+                plant_id: options.remove("plant").flatten(),
+                duration_seconds: parse_option(&mut options, "duration")?,
+                volume_ml: parse_option(&mut options, "volume")?,
+                force: options.remove("force").is_some()
+            }),
+            Some("photo") => Command::Photo {
+                out: options.remove("out").flatten().ok_or_else(|| usage("photo needs --out".to_owned()))?
+            },
+            Some("switch") => match (positionals.next().as_deref(), positionals.next(), positionals.next().as_deref()) {
+                (Some("set"), Some(name), Some("on")) => Command::Switch { name, enabled: true },
+                (Some("set"), Some(name), Some("off")) => Command::Switch { name, enabled: false },
+                _ => return Err(usage("expected switch set <name> on|off".to_owned()))
+            },
+            Some("climate") => match positionals.next().as_deref() {
+                Some("show") => Command::ClimateShow,
+                _ => return Err(usage("expected climate show".to_owned()))
+            },
+            Some("help") => Command::Help,
+            // config path as the only argument is kept for existing setups
+            Some(path) if config_path.is_none() => {
+                config_path = Some(path.to_owned());
+                Command::Serve
+            },
+            Some(other) => return Err(usage(format!("unknown command {}", other)))
+        };
+
+        // config path can also follow the command, like `rpi_home unit-file config.json`
+        if let Some(path) = positionals.next() {
+            if config_path.is_some() {
+                return Err(usage(format!("unexpected argument {}", path)));
+            }
+            config_path = Some(path);
+        }
+
+        let command = match options.remove("help") {
+            Some(_) => Command::Help,
+            None => command
+        };
+
+        let remote = options.remove("remote").flatten();
+        let key = options.remove("key").flatten();
+
+        if let Some(name) = options.keys().next() {
+            return Err(usage(format!("unknown option --{}", name)));
+        }
+
+        if let Some(extra) = positionals.next() {
+            return Err(usage(format!("unexpected argument {}", extra)));
+        }
+
+        if let Command::Water(water) = &command {
+            if water.plant_id.is_none() && water.duration_seconds.is_none() && water.volume_ml.is_none() {
+                return Err(usage("water needs --plant, --duration or --volume".to_owned()));
+            }
+        }
+
+        Ok(Args {
+            command,
+            config_path: config_path.unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_owned()),
+            remote,
+            key
+        })
+    }
+}
+
+fn parse_option<T: std::str::FromStr>(options: &mut HashMap<String, Option<String>>, name: &str) -> Result<Option<T>, CliError> {
+    match options.remove(name).flatten() {
+        Some(v) => match v.parse() {
+            Ok(v) => Ok(Some(v)),
+            Err(_) => Err(usage(format!("invalid --{} value {}", name, v)))
+        },
+        None => Ok(None)
+    }
+}
+
+fn usage(message: String) -> CliError {
+    CliError::Usage(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, CliError> {
+        Args::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    fn usage_error(args: &[&str]) -> String {
+        match parse(args) {
+            Err(CliError::Usage(message)) => message,
+            other => panic!("expected usage error for {:?}, got {:?}", args, other)
+        }
+    }
+
+    #[test]
+    fn serves_with_default_or_legacy_config() {
+        let args = parse(&[]).unwrap();
+        assert!(matches!(args.command, Command::Serve));
+        assert_eq!(args.config_path, DEFAULT_CONFIG_PATH);
+
+        let args = parse(&["home.json"]).unwrap();
+        assert!(matches!(args.command, Command::Serve));
+        assert_eq!(args.config_path, "home.json");
+
+        let args = parse(&["unit-file", "home.json"]).unwrap();
+        assert!(matches!(args.command, Command::UnitFile));
+        assert_eq!(args.config_path, "home.json");
+
+        assert_eq!(usage_error(&["home.json", "--config", "other.json"]), "unknown command home.json");
+    }
+
+    #[test]
+    fn parses_switch() {
+        let args = parse(&["switch", "set", "lamp", "on", "--remote", "http://pi:8080", "--key", "secret"]).unwrap();
+        match args.command {
+            Command::Switch { name, enabled } => {
+                assert_eq!(name, "lamp");
+                assert!(enabled);
+            },
+            other => panic!("unexpected command {:?}", other)
+        }
+        assert_eq!(args.remote.as_deref(), Some("http://pi:8080"));
+        assert_eq!(args.key.as_deref(), Some("secret"));
+
+        assert!(matches!(parse(&["switch", "set", "lamp", "off"]).unwrap().command, Command::Switch { enabled: false, .. }));
+        assert_eq!(usage_error(&["switch", "set", "lamp", "toggle"]), "expected switch set <name> on|off");
+        assert_eq!(usage_error(&["switch", "set", "lamp"]), "expected switch set <name> on|off");
+    }
+
+    #[test]
+    fn rejects_unknown_and_incomplete_options() {
+        assert_eq!(usage_error(&["serve", "--verbose", "1"]), "unknown option --verbose");
+        assert_eq!(usage_error(&["serve", "--config"]), "option --config needs a value");
+        assert_eq!(usage_error(&["water", "--duration", "ten"]), "invalid --duration value ten");
+        assert_eq!(usage_error(&["reboot", "--config", "home.json"]), "unknown command reboot");
+        assert!(matches!(parse(&["serve", "--help"]).unwrap().command, Command::Help));
+    }
+
+    #[test]
+    fn parses_water() {
+        assert_eq!(usage_error(&["water", "--pump", "main"]), "water needs --plant, --duration or --volume");
+
+        let args = parse(&["water", "--pump", "main", "--volume", "150", "--force"]).unwrap();
+        match args.command {
+            Command::Water(water) => {
+                assert_eq!(water.pump_id.as_deref(), Some("main"));
+                assert_eq!(water.volume_ml, Some(150.0));
+                assert_eq!(water.duration_seconds, None);
+                assert!(water.force);
+            },
+            other => panic!("unexpected command {:?}", other)
+        }
+
+        assert!(matches!(parse(&["water", "--plant", "basil"]).unwrap().command, Command::Water(WaterArgs { force: false, .. })));
+    }
+}
\ No newline at end of file
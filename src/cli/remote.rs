@@ -0,0 +1,83 @@
+use std::fs;
+
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Method, Request};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::cli::{CliError, Command, WaterArgs};
+
+/// Calls a running server over the HTTP API with the protected key.
+pub struct Remote {
+    url: String,
+    key: String,
+    client: Client<HttpConnector>
+}
+
+impl Remote {
+    pub fn new(url: &str, key: &str) -> Self {
+        Remote {
+            url: url.trim_end_matches('/').to_owned(),
+            key: key.to_owned(),
+            client: Client::new()
+        }
+    }
+
+    pub async fn run(&self, command: &Command) -> Result<(), CliError> {
+        let output = match command {
+            Command::Water(args) => self.water(args).await?,
+            Command::Photo { out } => {
+                let photo = self.call(Method::GET, "camera-image", Body::empty()).await?;
+                fs::write(out, &photo)?;
+                println!("photo saved to {} ({} bytes)", out, photo.len());
+                return Ok(());
+            },
+            Command::Switch { name, enabled } => self.post("set-switch", &json!({ "name": name, "value": enabled })).await?,
+            Command::ClimateShow => self.post("get-climate", &json!({})).await?,
+            other => return Err(CliError::Usage(format!("{:?} can not be called with --remote", other)))
+        };
+
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        Ok(())
+    }
+
+    async fn water(&self, args: &WaterArgs) -> Result<Value, CliError> {
+        let input = json!({
+            "key": &self.key,
+            "pump_id": &args.pump_id,
+            "plant_id": &args.plant_id,
+            "duration_seconds": args.duration_seconds.unwrap_or(0),
+            "volume_ml": args.volume_ml,
+            "force": args.force
+        });
+
+        self.post("water", &input).await
+    }
+
+    async fn post<I: Serialize>(&self, method: &str, input: &I) -> Result<Value, CliError> {
+        let input = serde_json::to_vec(input)?;
+        let output = self.call(Method::POST, method, Body::from(input)).await?;
+        Ok(serde_json::from_slice(&output)?)
+    }
+
+    async fn call(&self, http_method: Method, method: &str, body: Body) -> Result<Bytes, CliError> {
+        let request = Request::builder()
+            .method(http_method)
+            .uri(format!("{}/{}", &self.url, method))
+            .header("Protected-Key", &self.key)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)?;
+
+        let response = self.client.request(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+
+        if !status.is_success() {
+            return Err(CliError::Remote(status, String::from_utf8_lossy(&body).into_owned()));
+        }
+
+        Ok(body)
+    }
+}
\ No newline at end of file
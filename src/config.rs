@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::File;
 use std::path::Path;
@@ -6,11 +7,366 @@ use std::io::{Error, Read};
 use serde_json;
 use serde::{Deserialize, Serialize};
 
+use crate::services::climate::ConditionerMode;
+use crate::utils::image_metrics::Region;
+use crate::utils::ir::IrProtocol;
+use crate::utils::rf433::Protocol;
+use crate::utils::servo::{ServoCalibration, ServoPin};
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub address: String,
     pub log_config_path: String,
-    pub protected_key: String
+    pub protected_key: String,
+    /// Read-only key for camera image and stream URLs, which keep the key in browser history and logs.
+    pub view_key: Option<String>,
+    #[serde(default = "default_state_path")]
+    pub state_path: String,
+    /// Time for in-flight requests and background tasks to finish on shutdown.
+    #[serde(default = "default_drain_timeout")]
+    pub drain_timeout_seconds: u64,
+    #[serde(default = "default_water_pumps")]
+    pub water_pumps: Vec<WaterPumpConfig>,
+    #[serde(default = "default_water_sensors")]
+    pub water_sensors: Vec<WaterSensorConfig>,
+    #[serde(default)]
+    pub plants: Vec<PlantConfig>,
+    pub adc: Option<AdcConfig>,
+    #[serde(default)]
+    pub soil_sensors: Vec<SoilSensorConfig>,
+    pub watering_scheduler: Option<WateringSchedulerConfig>,
+    #[serde(default)]
+    pub climate_sensors: Vec<ClimateSensorConfig>,
+    #[serde(default = "default_climate_poll_interval")]
+    pub climate_poll_interval_seconds: u64,
+    #[serde(default = "default_w1_devices_path")]
+    pub w1_devices_path: String,
+    pub rf433_receiver: Option<Rf433ReceiverConfig>,
+    #[serde(default)]
+    pub climate_staleness: StalenessConfig,
+    pub ir_transmitter: Option<IrTransmitterConfig>,
+    #[serde(default = "default_conditioners")]
+    pub conditioners: Vec<ConditionerConfig>,
+    #[serde(default)]
+    pub climate_modes: ClimateModesConfig,
+    pub timelapse: Option<TimelapseConfig>,
+    #[serde(default)]
+    pub camera: CameraConfig,
+    pub motion: Option<MotionConfig>,
+    #[serde(default = "default_servos")]
+    pub servos: Vec<ServoConfig>,
+    pub camera_rig: Option<CameraRigConfig>,
+    pub watchdog: Option<WatchdogConfig>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WaterPumpConfig {
+    pub id: String,
+    pub name: String,
+    pub pin: u8,
+    pub water_sensor_id: Option<String>,
+    pub flow_ml_per_second: Option<f32>,
+    /// Rated power used for energy estimation.
+    pub power_watts: Option<f32>,
+    /// Longer runs are rejected, a pump running longer is forced off.
+    #[serde(default = "default_pump_max_runtime")]
+    pub max_runtime_seconds: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WaterSensorConfig {
+    pub id: String,
+    pub name: String,
+    pub power_pin: u8,
+    pub in_pin: u8
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlantConfig {
+    pub id: String,
+    pub name: String,
+    pub pump_id: String,
+    pub water_sensor_id: Option<String>,
+    pub amount_ml: f32,
+    pub min_interval_seconds: u64,
+    pub max_daily_ml: f32,
+    #[serde(default)]
+    pub auto_watering: bool,
+    pub soil_sensor_id: Option<String>,
+    pub moisture_threshold: Option<f32>
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum AdcKind {
+    Mcp3008,
+    Ads1115,
+    Simulated
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AdcConfig {
+    pub kind: AdcKind,
+    #[serde(default = "default_ads1115_address")]
+    pub address: u16,
+    #[serde(default)]
+    pub simulated_values: HashMap<u8, u16>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SoilSensorConfig {
+    pub id: String,
+    pub name: String,
+    pub channel: u8,
+    pub dry: u16,
+    pub wet: u16
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WateringSchedulerConfig {
+    pub check_interval_seconds: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ClimateSensorKind {
+    Ds18b20 { device_id: String },
+    Dht22 { pin: u8 },
+    Bme280 {
+        #[serde(default = "default_bme280_address")]
+        address: u16
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClimateTarget {
+    Sensor,
+    Bedroom,
+    Living,
+    Weather
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ClimateSensorConfig {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: ClimateSensorKind,
+    pub target: ClimateTarget,
+    pub channel: Option<i32>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Rf433ReceiverConfig {
+    pub pin: u8,
+    pub protocols: Vec<Protocol>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StalenessConfig {
+    #[serde(default = "default_room_max_age")]
+    pub room_max_age_seconds: u64,
+    #[serde(default = "default_weather_max_age")]
+    pub weather_max_age_seconds: u64,
+    #[serde(default)]
+    pub expected_weather_channels: Vec<i32>
+}
+
+impl Default for StalenessConfig {
+    fn default() -> Self {
+        StalenessConfig {
+            room_max_age_seconds: default_room_max_age(),
+            weather_max_age_seconds: default_weather_max_age(),
+            expected_weather_channels: Vec::new()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IrTransmitterConfig {
+    pub pin: u8,
+    #[serde(default = "default_carrier_hz")]
+    pub carrier_hz: u32
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConditionerConfig {
+    pub id: String,
+    pub name: String,
+    pub room: ClimateTarget,
+    #[serde(default = "default_conditioner_modes")]
+    pub modes: Vec<ConditionerMode>,
+    #[serde(default = "default_conditioner_min_temperature")]
+    pub min_temperature: i32,
+    #[serde(default = "default_conditioner_max_temperature")]
+    pub max_temperature: i32,
+    /// Conditioners without protocol are not controlled by IR.
+    pub ir_protocol: Option<IrProtocol>,
+    /// Rated power used for energy estimation.
+    pub power_watts: Option<f32>
+}
+
+/// Energy saving setpoints which override climate programs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EnergySavingConfig {
+    pub cool_temperature: i32,
+    pub heat_temperature: i32
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ClimateModesConfig {
+    pub away: EnergySavingConfig,
+    pub vacation: EnergySavingConfig
+}
+
+impl Default for ClimateModesConfig {
+    fn default() -> Self {
+        ClimateModesConfig {
+            away: EnergySavingConfig {
+                cool_temperature: 27,
+                heat_temperature: 18
+            },
+            vacation: EnergySavingConfig {
+                cool_temperature: 30,
+                heat_temperature: 14
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CameraConfig {
+    /// How long the last photo is returned to image requests instead of a new one.
+    #[serde(default = "default_camera_cache_seconds")]
+    pub cache_seconds: u64,
+    #[serde(default = "default_camera_warm_up")]
+    pub warm_up_millis: u64,
+    /// How long the camera stays active after the last photo, zero deactivates it right away.
+    #[serde(default)]
+    pub idle_seconds: u64,
+    /// Synthetic frames instead of the real device.
+    #[serde(default)]
+    pub simulated: bool,
+    /// Size of preview frames, which are captured for motion detection and the live stream.
+    #[serde(default = "default_camera_preview_width")]
+    pub preview_width: u32,
+    #[serde(default = "default_camera_preview_height")]
+    pub preview_height: u32,
+    #[serde(default)]
+    pub stream: StreamConfig
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StreamConfig {
+    #[serde(default = "default_stream_fps")]
+    pub fps: u32,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    #[serde(default = "default_stream_quality")]
+    pub quality: u8,
+    #[serde(default = "default_stream_max_viewers")]
+    pub max_viewers: usize
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        StreamConfig {
+            fps: default_stream_fps(),
+            width: None,
+            height: None,
+            quality: default_stream_quality(),
+            max_viewers: default_stream_max_viewers()
+        }
+    }
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        CameraConfig {
+            cache_seconds: default_camera_cache_seconds(),
+            warm_up_millis: default_camera_warm_up(),
+            idle_seconds: 0,
+            simulated: false,
+            preview_width: default_camera_preview_width(),
+            preview_height: default_camera_preview_height(),
+            stream: StreamConfig::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimelapseConfig {
+    #[serde(default = "default_timelapse_path")]
+    pub path: String,
+    pub interval_seconds: u64,
+    /// Daily capture window in local "HH:MM" time, frames are captured all day if not set.
+    pub start: Option<String>,
+    pub end: Option<String>,
+    #[serde(default = "default_timelapse_quota")]
+    pub quota_mb: u64,
+    /// Region with plants used for the green ratio, whole frame by default.
+    #[serde(default)]
+    pub roi: Region,
+    /// Camera rig poses captured one by one on every time-lapse tick, frames of each pose are kept separately.
+    #[serde(default)]
+    pub poses: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MotionConfig {
+    #[serde(default = "default_motion_path")]
+    pub path: String,
+    #[serde(default = "default_motion_interval")]
+    pub interval_millis: u64,
+    /// Mean difference of consecutive frames, 0 - 1, which is reported as motion.
+    #[serde(default = "default_motion_threshold")]
+    pub threshold: f32,
+    /// Minimal time between two events.
+    #[serde(default = "default_motion_cooldown")]
+    pub cooldown_seconds: u64,
+    #[serde(default = "default_motion_max_events")]
+    pub max_events: usize
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ServoConfig {
+    pub id: String,
+    pub name: String,
+    pub pin: ServoPin,
+    /// Calibrated values saved by `calibrate-servo` override this one.
+    #[serde(default)]
+    pub calibration: ServoCalibration,
+    /// Speed of smooth movement, zero turns the servo at once.
+    #[serde(default = "default_servo_speed")]
+    pub speed_degrees_per_second: f32
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PoseConfig {
+    pub pan: f32,
+    pub tilt: f32
+}
+
+/// Camera mounted on two servos.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CameraRigConfig {
+    pub pan_servo_id: String,
+    pub tilt_servo_id: String,
+    /// Time for the rig to stop shaking before a photo.
+    #[serde(default = "default_rig_settle")]
+    pub settle_millis: u64,
+    #[serde(default)]
+    pub poses: BTreeMap<String, PoseConfig>
+}
+
+/// Linux hardware watchdog, the Pi reboots if the server stops feeding it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WatchdogConfig {
+    #[serde(default = "default_watchdog_path")]
+    pub path: String,
+    /// Should be less than the watchdog timeout, which is 15 seconds on the Pi.
+    #[serde(default = "default_watchdog_interval")]
+    pub interval_seconds: u64
 }
 
 impl Config {
@@ -26,4 +382,184 @@ impl Config {
 
         Ok(config)
     }
-}
\ No newline at end of file
+}
+
+fn default_state_path() -> String {
+    "state".to_owned()
+}
+
+fn default_ads1115_address() -> u16 {
+    0x48
+}
+
+fn default_bme280_address() -> u16 {
+    0x76
+}
+
+fn default_climate_poll_interval() -> u64 {
+    60
+}
+
+fn default_w1_devices_path() -> String {
+    "/sys/bus/w1/devices".to_owned()
+}
+
+fn default_room_max_age() -> u64 {
+    15 * 60
+}
+
+fn default_weather_max_age() -> u64 {
+    30 * 60
+}
+
+fn default_carrier_hz() -> u32 {
+    38_000
+}
+
+fn default_conditioners() -> Vec<ConditionerConfig> {
+    vec![
+        ConditionerConfig {
+            id: "0".to_owned(),
+            name: "Bedroom".to_owned(),
+            room: ClimateTarget::Bedroom,
+            modes: default_conditioner_modes(),
+            min_temperature: default_conditioner_min_temperature(),
+            max_temperature: default_conditioner_max_temperature(),
+            ir_protocol: None,
+            power_watts: None
+        },
+        ConditionerConfig {
+            id: "1".to_owned(),
+            name: "Living room".to_owned(),
+            room: ClimateTarget::Living,
+            modes: default_conditioner_modes(),
+            min_temperature: default_conditioner_min_temperature(),
+            max_temperature: default_conditioner_max_temperature(),
+            ir_protocol: None,
+            power_watts: None
+        }
+    ]
+}
+
+fn default_conditioner_modes() -> Vec<ConditionerMode> {
+    vec![ConditionerMode::Auto, ConditionerMode::Cool, ConditionerMode::Dry, ConditionerMode::Fan, ConditionerMode::Heat]
+}
+
+fn default_conditioner_min_temperature() -> i32 {
+    16
+}
+
+fn default_conditioner_max_temperature() -> i32 {
+    30
+}
+
+fn default_camera_cache_seconds() -> u64 {
+    5
+}
+
+fn default_camera_warm_up() -> u64 {
+    2000
+}
+
+fn default_camera_preview_width() -> u32 {
+    320
+}
+
+fn default_camera_preview_height() -> u32 {
+    240
+}
+
+fn default_stream_fps() -> u32 {
+    5
+}
+
+fn default_stream_quality() -> u8 {
+    70
+}
+
+fn default_stream_max_viewers() -> usize {
+    2
+}
+
+fn default_timelapse_path() -> String {
+    "timelapse".to_owned()
+}
+
+fn default_timelapse_quota() -> u64 {
+    512
+}
+
+fn default_motion_path() -> String {
+    "motion".to_owned()
+}
+
+fn default_motion_interval() -> u64 {
+    1000
+}
+
+fn default_motion_threshold() -> f32 {
+    0.05
+}
+
+fn default_motion_cooldown() -> u64 {
+    10
+}
+
+fn default_motion_max_events() -> usize {
+    100
+}
+
+fn default_servo_speed() -> f32 {
+    60.0
+}
+
+fn default_servos() -> Vec<ServoConfig> {
+    vec![ServoConfig {
+        id: "default".to_owned(),
+        name: "Default".to_owned(),
+        pin: ServoPin::Pwm0,
+        calibration: ServoCalibration::default(),
+        speed_degrees_per_second: default_servo_speed()
+    }]
+}
+
+fn default_rig_settle() -> u64 {
+    500
+}
+
+fn default_water_pumps() -> Vec<WaterPumpConfig> {
+    vec![WaterPumpConfig {
+        id: "default".to_owned(),
+        name: "Default".to_owned(),
+        pin: 5,
+        water_sensor_id: Some("default".to_owned()),
+        flow_ml_per_second: None,
+        power_watts: None,
+        max_runtime_seconds: default_pump_max_runtime()
+    }]
+}
+
+fn default_drain_timeout() -> u64 {
+    20
+}
+
+fn default_pump_max_runtime() -> u64 {
+    300
+}
+
+fn default_watchdog_path() -> String {
+    "/dev/watchdog".to_owned()
+}
+
+fn default_watchdog_interval() -> u64 {
+    5
+}
+
+fn default_water_sensors() -> Vec<WaterSensorConfig> {
+    vec![WaterSensorConfig {
+        id: "default".to_owned(),
+        name: "Default".to_owned(),
+        power_pin: 24,
+        in_pin: 23
+    }]
+}
@@ -5,20 +5,46 @@ use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use hyper::Server;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
 use hyper::service::{make_service_fn, service_fn};
 
 use server::RpiHomeContext;
 use config::Config;
+use cli::{Args, CliError, Command};
+use cli::local;
+use cli::remote::Remote;
 use utils::camera::Camera;
 
 use requests::*;
-use crate::utils::water_sensor::WaterSensor;
-use crate::utils::water_pump::WaterPump;
-use crate::utils::servo::Servo;
 use crate::services::climate::Climate;
+use crate::services::climate_poller::ClimatePoller;
+use crate::services::climate_schedule::ClimateSchedule;
+use crate::services::conditioner_remote::ConditionerRemote;
+use crate::utils::rf433::receiver::Receiver;
 use crate::services::switches::Switches;
+use crate::services::water::Water;
+use crate::services::plants::Plants;
+use crate::services::camera_rig::CameraRig;
+use crate::services::camera_service::CameraService;
+use crate::services::health::Health;
+use crate::services::metrics::Metrics;
+use crate::services::mjpeg_stream::MjpegStream;
+use crate::services::motion::MotionDetector;
+use crate::services::photo_cache::PhotoCache;
+use crate::services::safety::{Actuator, Safety};
+use crate::services::servos::ServoControl;
+use crate::services::shutdown::Shutdown;
+use crate::services::soil::Soil;
+use crate::services::timelapse::Timelapse;
+use crate::services::usage::Usage;
+use crate::services::watering_scheduler::WateringScheduler;
+use crate::server::server_error::{LogicError, ServerError};
+use crate::utils::storage::Storage;
+use crate::utils::systemd;
 
 mod config;
 mod server;
@@ -26,22 +52,63 @@ mod requests;
 mod utils;
 mod services;
 mod commands;
+mod cli;
 
 #[tokio::main]
 async fn main() {
-    let mut args = std::env::args();
+    let args = match Args::parse(std::env::args().skip(1)) { // skip exe
+        Ok(a) => a,
+        Err(e) => exit_with(e)
+    };
 
-    args.next(); // skip exe
-    let config_path = match args.next() { // path to config
-        Some(c) => c,
-        None => panic!("first argument should be path to config file")
+    if let Command::Help = args.command {
+        print!("{}", cli::USAGE);
+        return;
+    }
+
+    // remote calls need config only for the protected key
+    let config = match (&args.remote, &args.key) {
+        (Some(_), Some(_)) => None,
+        _ => match Config::from_file(&args.config_path) {
+            Ok(c) => Some(c),
+            Err(e) => exit_with(CliError::Failed(format!("error on config {} read: {}", &args.config_path, e)))
+        }
     };
 
-    let config = match Config::from_file(&config_path) {
-        Ok(c) => c,
-        Err(e) => panic!("error on config read: {}", e)
+    let result = match (&args.command, &args.remote, config) {
+        (Command::Serve, None, Some(config)) => {
+            serve(config).await;
+            Ok(())
+        },
+        (Command::CheckConfig, None, Some(config)) => local::check_config(&config),
+        (Command::UnitFile, None, Some(config)) => systemd::unit_file(&config, &args.config_path)
+            .map(|u| print!("{}", u))
+            .map_err(CliError::from),
+        (command, Some(url), config) => {
+            let key = args.key.clone()
+                .or_else(|| config.map(|c| c.protected_key))
+                .unwrap_or_default();
+            Remote::new(url, &key).run(command).await
+        },
+        (command, None, Some(config)) => local::run(&config, command).await,
+        (_, None, None) => unreachable!("config is read for local commands")
     };
 
+    if let Err(e) = result {
+        exit_with(e);
+    }
+}
+
+fn exit_with(error: CliError) -> ! {
+    eprintln!("{}", error);
+    if let CliError::Usage(_) = error {
+        eprintln!();
+        eprint!("{}", cli::USAGE);
+    }
+    std::process::exit(1);
+}
+
+async fn serve(config: Config) {
     if let Err(e) = log4rs::init_file(&config.log_config_path, Default::default()) {
         panic!("error on logger init: {}", e);
     }
@@ -51,43 +118,217 @@ async fn main() {
         Err(e) => panic!("error on address parse {}", e)
     };
 
-    let camera = match Camera::new() {
+    let shutdown = Shutdown::new();
+    let mut tasks = Vec::new();
+
+    let camera = if config.camera.simulated {
+        Camera::Simulated
+    } else {
+        match Camera::new() {
+            Ok(c) => c,
+            Err(e) => panic!("error on camera creation {}", e)
+        }
+    };
+
+    let camera = match CameraService::start(&config.camera, camera) {
         Ok(c) => Arc::new(c),
-        Err(e) => panic!("error on camera creation {}", e)
+        Err(e) => panic!("error on camera service creation {}", e)
+    };
+
+    let photos = Arc::new(PhotoCache::new(&config.camera, &camera));
+    let stream = Arc::new(MjpegStream::new(&config.camera.stream, &camera, &shutdown));
+
+    let storage = match Storage::new(&config.state_path) {
+        Ok(s) => Arc::new(s),
+        Err(e) => panic!("error on state storage creation {}", e)
     };
 
-    let water_sensor = match WaterSensor::new() {
-        Ok(ws) => Arc::new(ws),
-        Err(e) => panic!("error on water sensor creation {}", e)
+    let usage = match Usage::new(&config.conditioners, &config.water_pumps, &storage) {
+        Ok(u) => Arc::new(u),
+        Err(e) => panic!("error on usage accounting creation {}", e)
     };
 
-    let water_pump = match WaterPump::new() {
-        Ok(wp) => Arc::new(wp),
-        Err(e) => panic!("error on water pump creation {}", e)
+    let motion = match &config.motion {
+        Some(motion_config) => match MotionDetector::new(motion_config, &storage) {
+            Ok(m) => {
+                let m = Arc::new(m);
+                tasks.push(MotionDetector::start(&m, &camera, &shutdown));
+
+                let mut events = m.subscribe();
+                tokio::spawn(async move {
+                    loop {
+                        match events.recv().await {
+                            Ok(event) => info!("motion detected at {}, change {}", event.time, event.change),
+                            Err(RecvError::Lagged(_)) => continue,
+                            Err(RecvError::Closed) => break
+                        }
+                    }
+                });
+
+                Some(m)
+            },
+            Err(e) => panic!("error on motion detector creation {}", e)
+        },
+        None => None
     };
 
-    let servo = match Servo::new() {
+    let water = match Water::new(&config.water_pumps, &config.water_sensors, &storage, &usage) {
+        Ok(w) => Arc::new(w),
+        Err(e) => panic!("error on water system creation {}", e)
+    };
+
+    let servo = match ServoControl::new(&config.servos, &storage) {
         Ok(s) => Arc::new(s),
         Err(e) => panic!("error on servo creation {}", e)
     };
 
-    let climate = Arc::new(Climate::new());
+    let conditioner_remote = match &config.ir_transmitter {
+        Some(ir_config) => match ConditionerRemote::new(ir_config, &config.conditioners) {
+            Ok(r) => Some(Arc::new(r)),
+            Err(e) => panic!("error on IR transmitter creation {}", e)
+        },
+        None => None
+    };
+
+    let mut actuators : Vec<Arc<dyn Actuator>> = vec![water.clone(), servo.clone()];
+    if let Some(remote) = &conditioner_remote {
+        actuators.push(remote.clone());
+    }
+
+    let safety = Arc::new(Safety::new(&config.watchdog, actuators));
+    Safety::install_panic_hook(&safety);
+    Safety::listen_signals(&safety, &shutdown);
+    Safety::start(&safety);
+
+    let soil = match Soil::new(&config.adc, &config.soil_sensors) {
+        Ok(s) => Arc::new(s),
+        Err(e) => panic!("error on soil sensors creation {}", e)
+    };
+
+    let plants = match Plants::new(&config.plants, &water, &soil, &storage) {
+        Ok(p) => Arc::new(p),
+        Err(e) => panic!("error on plants creation {}", e)
+    };
+
+    if let Some(scheduler_config) = &config.watering_scheduler {
+        tasks.push(WateringScheduler::start(scheduler_config, &plants, &shutdown));
+    }
+
+    let camera_rig = match &config.camera_rig {
+        Some(rig_config) => match CameraRig::new(rig_config, &servo, &camera) {
+            Ok(r) => Some(Arc::new(r)),
+            Err(e) => panic!("error on camera rig creation {}", e)
+        },
+        None => None
+    };
+
+    let timelapse = match &config.timelapse {
+        Some(timelapse_config) => match Timelapse::new(timelapse_config) {
+            Ok(t) => {
+                let t = Arc::new(t);
+                for pose in t.poses() {
+                    let result = match &camera_rig {
+                        Some(rig) => rig.check_pose(pose),
+                        None => Err(ServerError::from(LogicError::CameraPoseNotFound))
+                    };
+                    if let Err(e) = result {
+                        panic!("error on time-lapse pose {} {}", pose, e);
+                    }
+                }
+
+                tasks.push(Timelapse::start(&t, &camera, camera_rig.clone(), &shutdown));
+                Some(t)
+            },
+            Err(e) => panic!("error on time-lapse creation {}", e)
+        },
+        None => None
+    };
+
+    let climate_schedule = match ClimateSchedule::new(&config.climate_modes, &storage) {
+        Ok(s) => Arc::new(s),
+        Err(e) => panic!("error on climate schedule creation {}", e)
+    };
+
+    let climate = Arc::new(Climate::new(&config.climate_staleness, &config.conditioners, &climate_schedule, conditioner_remote, &usage));
+    tasks.push(Climate::start_schedule(&climate, &shutdown));
+    if !config.climate_sensors.is_empty() {
+        match ClimatePoller::start(&config.climate_sensors, &config.w1_devices_path, config.climate_poll_interval_seconds, &climate, &shutdown) {
+            Ok(t) => tasks.push(t),
+            Err(e) => panic!("error on climate sensors creation {}", e)
+        }
+    }
+
+    let _rf433_receiver = match &config.rf433_receiver {
+        Some(rf433_config) => {
+            let climate = climate.clone();
+            let receiver = Receiver::start(rf433_config.pin, &rf433_config.protocols, move |reading| {
+                info!("433 MHz reading: {:?}", &reading);
+                if let Err(e) = climate.set_radio(reading) {
+                    error!("error on 433 MHz reading update: {}", e);
+                }
+            });
+
+            match receiver {
+                Ok(r) => Some(r),
+                Err(e) => panic!("error on 433 MHz receiver creation {}", e)
+            }
+        },
+        None => None
+    };
+
     let switches = Arc::new(Switches::new());
 
-    let mut context = RpiHomeContext::new();
+    let metrics = Arc::new(Metrics::new());
+
+    let health = Arc::new(Health::new(&camera, &storage, &water, &climate, &switches, &shutdown));
+    Health::start(&health);
+    tasks.push(Health::start_device_probes(&health, &shutdown));
+
+    let mut context = RpiHomeContext::new(&metrics);
     context.add_handler(echo_request::EchoRequest::new());
+    context.add_handler(health_request::HealthRequest::new(&config.protected_key, &health));
+    context.add_handler(ready_request::ReadyRequest::new(&config.protected_key, &health));
 
-    context.add_handler(get_camera_image_request::GetCameraImageRequest::new(&config.protected_key, &camera));
-    context.add_handler(is_enough_water_request::IsEnoughWaterRequest::new(&config.protected_key, &water_sensor));
-    context.add_handler(water_request::WaterRequest::new(&config.protected_key, &water_sensor, &water_pump));
+    context.add_handler(get_camera_image_request::GetCameraImageRequest::new(&config.protected_key, &photos));
+    context.add_handler(camera_jpeg_request::CameraJpegRequest::new(&config.protected_key, &config.view_key, &photos));
+    context.add_handler(camera_stream_request::CameraStreamRequest::new(&config.protected_key, &config.view_key, &stream));
+    if let Some(timelapse) = &timelapse {
+        context.add_handler(get_timelapse_frames_request::GetTimelapseFramesRequest::new(&config.protected_key, timelapse));
+        context.add_handler(get_timelapse_frame_request::GetTimelapseFrameRequest::new(&config.protected_key, timelapse));
+        context.add_handler(get_latest_frame_request::GetLatestFrameRequest::new(&config.protected_key, timelapse));
+        context.add_handler(get_growth_metrics_request::GetGrowthMetricsRequest::new(&config.protected_key, timelapse));
+    }
+    if let Some(motion) = &motion {
+        context.add_handler(get_motion_events_request::GetMotionEventsRequest::new(&config.protected_key, motion));
+        context.add_handler(motion_snapshot_request::MotionSnapshotRequest::new(&config.protected_key, &config.view_key, motion));
+    }
+    context.add_handler(is_enough_water_request::IsEnoughWaterRequest::new(&config.protected_key, &water));
+    context.add_handler(water_request::WaterRequest::new(&config.protected_key, &water, &plants));
+    context.add_handler(get_plants_request::GetPlantsRequest::new(&config.protected_key, &plants));
+    context.add_handler(calibrate_pump_request::CalibratePumpRequest::new(&config.protected_key, &water));
+    context.add_handler(get_soil_moisture_request::GetSoilMoistureRequest::new(&config.protected_key, &soil));
     context.add_handler(turn_servo_request::TurnServoRequest::new(&config.protected_key, &servo));
+    context.add_handler(get_servo_request::GetServoRequest::new(&config.protected_key, &servo));
+    context.add_handler(servo_preset_request::ServoPresetRequest::new(&config.protected_key, &servo));
+    context.add_handler(calibrate_servo_request::CalibrateServoRequest::new(&config.protected_key, &servo));
+    if let Some(camera_rig) = &camera_rig {
+        context.add_handler(move_camera_request::MoveCameraRequest::new(&config.protected_key, camera_rig));
+    }
 
     context.add_handler(conditioners_request::ConditionersRequest::new(&config.protected_key, &climate));
     context.add_handler(get_climate_request::GetClimateRequest::new(&config.protected_key, &climate));
     context.add_handler(set_climate_request::SetClimateRequest::new(&config.protected_key, &climate));
+    context.add_handler(get_conditioners_request::GetConditionersRequest::new(&config.protected_key, &climate));
+    context.add_handler(get_climate_schedule_request::GetClimateScheduleRequest::new(&config.protected_key, &climate));
+    context.add_handler(set_climate_programs_request::SetClimateProgramsRequest::new(&config.protected_key, &climate));
+    context.add_handler(set_climate_mode_request::SetClimateModeRequest::new(&config.protected_key, &climate));
+
+    context.add_handler(get_usage_request::GetUsageRequest::new(&config.protected_key, &usage));
 
     context.add_handler(is_enabled_request::IsEnabledRequest::new(&config.protected_key, &switches));
-    context.add_handler(set_switch_request::SwitchRequest::new(&config.protected_key, &switches));
+    context.add_handler(set_switch_request::SwitchRequest::new(&config.protected_key, &switches, &metrics));
+
+    context.add_handler(metrics_request::MetricsRequest::new(&config.protected_key, &metrics, &water, &climate, &switches));
 
     let context = Arc::new(context);
 
@@ -100,11 +341,76 @@ async fn main() {
         async move { Ok::<_, Infallible>(service) }
     });
 
-    let server = Server::bind(&socket_addr).serve(make_service);
+    let server_shutdown = shutdown.clone();
+    let builder = match systemd::listener() {
+        Ok(Some(listener)) => match Server::from_tcp(listener) {
+            Ok(b) => b,
+            Err(e) => panic!("error on activation socket use {}", e)
+        },
+        Ok(None) => Server::bind(&socket_addr),
+        Err(e) => panic!("error on activation socket take {}", e)
+    };
+
+    let server = builder.serve(make_service);
+    let local_addr = server.local_addr();
+    let server = server.with_graceful_shutdown(async move { server_shutdown.wait().await });
+    tokio::pin!(server);
+
+    println!("Listening on http://{}", local_addr);
+    if let Err(e) = safety.arm() {
+        panic!("error on hardware watchdog arm {}", e);
+    }
+    health.set_started();
+    if let Err(e) = systemd::notify(&format!("READY=1\nSTATUS=Listening on {}", local_addr)) {
+        error!("error on systemd ready notify: {}", e);
+    }
+
+    let stopped = tokio::select! {
+        result = &mut server => {
+            if let Err(e) = result {
+                eprintln!("server error: {}", e);
+            }
+            true
+        },
+        _ = shutdown.wait() => false
+    };
+    shutdown.trigger();
+
+    if let Err(e) = systemd::notify("STOPPING=1\nSTATUS=Draining requests") {
+        error!("error on systemd stopping notify: {}", e);
+    }
+
+    // server stops accepting connections on shutdown and waits for in-flight requests,
+    // background tasks finish their current iteration
+    let drain_timeout = Duration::from_secs(config.drain_timeout_seconds);
+    let drained = tokio::time::timeout(drain_timeout, async move {
+        if !stopped {
+            if let Err(e) = server.await {
+                error!("server error on shutdown: {}", e);
+            }
+        }
+        drain_tasks(tasks).await;
+    }).await.is_ok();
+
+    if !drained {
+        warn!("shutdown drain timeout expired, stopping anyway");
+    }
+
+    if let Err(e) = usage.flush() {
+        error!("error on usage save: {}", e);
+    }
+
+    safety.stop();
+    info!("server stopped");
 
-    println!("Listening on http://{}", socket_addr);
+    // runtime drop would wait for requests which are still blocked after the drain timeout
+    std::process::exit(0);
+}
 
-    if let Err(e) = server.await {
-        eprintln!("server error: {}", e);
+async fn drain_tasks(tasks: Vec<JoinHandle<()>>) {
+    for task in tasks {
+        if let Err(e) = task.await {
+            error!("background task failed: {}", e);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::{ServerError};
+use crate::services::water::Water;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+pub struct CalibratePumpRequest;
+
+impl CalibratePumpRequest {
+    pub fn new(key: &str, water: &Arc<Water>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("calibrate-pump")
+            .set_post(JsonMethodHandlerAdapter::new(StartCalibrationMethod {
+                water: water.clone()
+            }, key.clone()))
+            .set_put(JsonMethodHandlerAdapter::new(FinishCalibrationMethod {
+                water: water.clone()
+            }, key)))
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct StartInput {
+    key: Option<String>,
+    pump_id: Option<String>,
+    duration_seconds: u64
+}
+
+#[derive(Serialize, Debug)]
+pub struct StartOutput {
+    result: String
+}
+
+pub struct StartCalibrationMethod {
+    water: Arc<Water>
+}
+
+#[async_trait]
+impl JsonMethodHandler for StartCalibrationMethod {
+    type Input = StartInput;
+    type Output = StartOutput;
+
+    async fn process(&self, _parts: Parts, input: StartInput) -> Result<StartOutput, ServerError> {
+        info!("pump calibration started: pump {:?}, duration {}s", &input.pump_id, input.duration_seconds);
+
+        let duration = Duration::from_secs(input.duration_seconds);
+        let water = self.water.clone();
+        tokio::task::spawn_blocking(move || water.start_calibration(input.pump_id.as_deref(), duration)).await??;
+
+        Ok(StartOutput {
+            result: "Measure poured volume and send it to finish calibration".to_owned()
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a StartInput) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct FinishInput {
+    key: Option<String>,
+    pump_id: Option<String>,
+    volume_ml: f32
+}
+
+#[derive(Serialize, Debug)]
+pub struct FinishOutput {
+    flow_ml_per_second: f32
+}
+
+pub struct FinishCalibrationMethod {
+    water: Arc<Water>
+}
+
+#[async_trait]
+impl JsonMethodHandler for FinishCalibrationMethod {
+    type Input = FinishInput;
+    type Output = FinishOutput;
+
+    async fn process(&self, _parts: Parts, input: FinishInput) -> Result<FinishOutput, ServerError> {
+        Ok(FinishOutput {
+            flow_ml_per_second: self.water.finish_calibration(input.pump_id.as_deref(), input.volume_ml)?
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a FinishInput) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::servos::ServoControl;
+use crate::utils::servo::ServoCalibration;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    servo_id: Option<String>,
+    #[serde(flatten)]
+    calibration: ServoCalibration
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    result: String
+}
+
+pub struct CalibrateServoRequest {
+    servo: Arc<ServoControl>
+}
+
+impl CalibrateServoRequest {
+    pub fn new(key: &str, servo: &Arc<ServoControl>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("calibrate-servo")
+            .set_post(JsonMethodHandlerAdapter::new(CalibrateServoRequest {
+                servo: servo.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for CalibrateServoRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        self.servo.calibrate(input.servo_id.as_deref(), input.calibration)?;
+        Ok(Output {
+            result: "Success".to_owned()
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use async_trait::async_trait;
+use hyper::body::Bytes;
+use hyper::header::{CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use hyper::http::request::Parts;
+use hyper::{Body, Response, StatusCode};
+
+use crate::server::query;
+use crate::server::request_handler::{MethodHandler, RequestHandler};
+use crate::server::server_error::ServerError;
+use crate::services::photo_cache::{Photo, PhotoCache};
+use crate::utils::jpeg;
+
+const DEFAULT_QUALITY : u8 = 85;
+
+/// Returns photo as `image/jpeg`, supports `width`, `height` and `quality` query parameters for thumbnails.
+pub struct CameraJpegRequest {
+    photos: Arc<PhotoCache>,
+    key: String,
+    view_key: Option<String>
+}
+
+struct Query {
+    width: Option<u32>,
+    height: Option<u32>,
+    quality: Option<u8>
+}
+
+impl CameraJpegRequest {
+    pub fn new(key: &str, view_key: &Option<String>, photos: &Arc<PhotoCache>) -> Arc<RequestHandler> {
+        Arc::new(RequestHandler::new("camera-image")
+            .set_get(CameraJpegRequest {
+                photos: photos.clone(),
+                key: key.to_string(),
+                view_key: view_key.clone()
+            }))
+    }
+
+    fn is_not_modified(parts: &Parts, photo: &Photo, etag: &str) -> Result<bool, ServerError> {
+        if let Some(h) = parts.headers.get(IF_NONE_MATCH) {
+            return Ok(h.to_str()?.split(',').any(|t| t.trim() == etag || t.trim() == "*"));
+        }
+
+        if let Some(h) = parts.headers.get(IF_MODIFIED_SINCE) {
+            if let Ok(since) = httpdate::parse_http_date(h.to_str()?) {
+                let photo_secs = photo.time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+                let since_secs = since.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+                return Ok(photo_secs <= since_secs);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+impl Query {
+    fn parse(parts: &Parts) -> Query {
+        Query {
+            width: query::param(parts, "width").and_then(|v| v.parse().ok()),
+            height: query::param(parts, "height").and_then(|v| v.parse().ok()),
+            quality: query::param(parts, "quality").and_then(|v| v.parse().ok())
+        }
+    }
+
+    fn is_original(&self) -> bool {
+        self.width.is_none() && self.height.is_none() && self.quality.is_none()
+    }
+}
+
+#[async_trait]
+impl MethodHandler for CameraJpegRequest {
+    async fn process(&self, parts: Parts, _data: Bytes) -> Result<Response<Body>, ServerError> {
+        query::check_view_key(&parts, &self.key, &self.view_key)?;
+        let query = Query::parse(&parts);
+
+        let photo = self.photos.photo().await?;
+
+        let etag = if query.is_original() {
+            format!("\"{}\"", &photo.tag)
+        } else {
+            format!("\"{}-{}x{}q{}\"", &photo.tag, query.width.unwrap_or(0), query.height.unwrap_or(0), query.quality.unwrap_or(DEFAULT_QUALITY))
+        };
+
+        let builder = Response::builder()
+            .header(ETAG, &etag)
+            .header(LAST_MODIFIED, httpdate::fmt_http_date(photo.time))
+            .header(CACHE_CONTROL, "private, no-cache");
+
+        if CameraJpegRequest::is_not_modified(&parts, &photo, &etag)? {
+            return Ok(builder
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Body::empty())?);
+        }
+
+        let data = if query.is_original() {
+            photo.data.clone()
+        } else {
+            let (width, height) = (query.width, query.height);
+            let quality = query.quality.unwrap_or(DEFAULT_QUALITY);
+            let photo = photo.clone();
+            tokio::task::spawn_blocking(move || jpeg::resize(&photo.data, width, height, quality)).await??
+        };
+
+        Ok(builder
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "image/jpeg")
+            .header(CONTENT_LENGTH, data.len())
+            .body(Body::from(data))?)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::body::Bytes;
+use hyper::header::{CACHE_CONTROL, CONTENT_TYPE};
+use hyper::http::request::Parts;
+use hyper::{Body, Response, StatusCode};
+
+use crate::server::query;
+use crate::server::request_handler::{MethodHandler, RequestHandler};
+use crate::server::server_error::ServerError;
+use crate::services::mjpeg_stream::{BOUNDARY, MjpegStream};
+
+/// Live MJPEG stream, can be opened directly in a browser or an `<img>` tag.
+pub struct CameraStreamRequest {
+    stream: Arc<MjpegStream>,
+    key: String,
+    view_key: Option<String>
+}
+
+impl CameraStreamRequest {
+    pub fn new(key: &str, view_key: &Option<String>, stream: &Arc<MjpegStream>) -> Arc<RequestHandler> {
+        Arc::new(RequestHandler::new("camera-stream")
+            .set_get(CameraStreamRequest {
+                stream: stream.clone(),
+                key: key.to_string(),
+                view_key: view_key.clone()
+            }))
+    }
+}
+
+#[async_trait]
+impl MethodHandler for CameraStreamRequest {
+    async fn process(&self, parts: Parts, _data: Bytes) -> Result<Response<Body>, ServerError> {
+        query::check_view_key(&parts, &self.key, &self.view_key)?;
+
+        let body = self.stream.open()?;
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, format!("multipart/x-mixed-replace; boundary={}", BOUNDARY))
+            .header(CACHE_CONTROL, "no-cache")
+            .body(body)?)
+    }
+}
\ No newline at end of file
@@ -13,9 +13,9 @@ use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAd
 pub struct Input {
     key: Option<String>,
     sensors: Vec<WeatherSensor>,
-    sensor_temp: f32,
-    bedroom_temp: f32,
-    living_temp: f32
+    sensor_temp: Option<f32>,
+    bedroom_temp: Option<f32>,
+    living_temp: Option<f32>
 }
 
 #[derive(Serialize, Debug)]
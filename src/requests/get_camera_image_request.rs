@@ -5,7 +5,7 @@ use hyper::http::request::Parts;
 
 use crate::server::request_handler::RequestHandler;
 use crate::server::server_error::{ServerError};
-use crate::utils::camera::Camera;
+use crate::services::photo_cache::PhotoCache;
 
 use serde::{Deserialize, Serialize};
 use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
@@ -21,15 +21,15 @@ pub struct Output {
 }
 
 pub struct GetCameraImageRequest {
-    camera: Arc<Camera>
+    photos: Arc<PhotoCache>
 }
 
 impl GetCameraImageRequest {
-    pub fn new(key: &str, camera: &Arc<Camera>) -> Arc<RequestHandler> {
+    pub fn new(key: &str, photos: &Arc<PhotoCache>) -> Arc<RequestHandler> {
         let key = Some(key.to_string());
         Arc::new(RequestHandler::new("get-camera-image")
             .set_post(JsonMethodHandlerAdapter::new(GetCameraImageRequest {
-                camera: camera.clone()
+                photos: photos.clone()
             }, key)))
     }
 }
@@ -40,11 +40,11 @@ impl JsonMethodHandler for GetCameraImageRequest {
     type Output = Output;
 
     async fn process(&self, _: Parts, _: Input) -> Result<Output, ServerError> {
-        let photo = self.camera.make_photo()?;
-        let photo_len = photo.len();
+        let photo = self.photos.photo().await?;
+        let photo_len = photo.data.len();
 
         info!("Encoding photo to base64...");
-        let photo_encoded = general_purpose::STANDARD.encode(photo);
+        let photo_encoded = general_purpose::STANDARD.encode(&photo.data);
         info!("Photo converted to base64 (before: {}, after: {})", photo_len, photo_encoded.len());
 
         info!("Sending photo...");
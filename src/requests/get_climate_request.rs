@@ -4,7 +4,7 @@ use hyper::http::request::Parts;
 
 use crate::server::request_handler::RequestHandler;
 use crate::server::server_error::{ServerError};
-use crate::services::climate::{Conditioner, Climate, Sensors};
+use crate::services::climate::{Conditioner, Climate, Sensors, SensorsHealth};
 
 use serde::{Deserialize, Serialize};
 use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
@@ -17,7 +17,9 @@ pub struct Input {
 #[derive(Serialize, Debug)]
 pub struct Output {
     conditioners: Vec<Conditioner>,
-    sensors: Sensors
+    effective_conditioners: Vec<Conditioner>,
+    sensors: Sensors,
+    health: SensorsHealth
 }
 
 pub struct GetClimateRequest {
@@ -41,10 +43,14 @@ impl JsonMethodHandler for GetClimateRequest {
 
     async fn process(&self, _parts: Parts, _input: Input) -> Result<Output, ServerError> {
         let conditioners = self.climate.conditioners()?;
+        let effective_conditioners = self.climate.effective_conditioners()?;
         let sensors = self.climate.sensors()?;
+        let health = self.climate.health()?;
         Ok(Output {
             conditioners,
-            sensors
+            effective_conditioners,
+            sensors,
+            health
         })
     }
 
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::climate::Climate;
+use crate::services::climate_schedule::ScheduleState;
+
+use serde::Deserialize;
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>
+}
+
+pub struct GetClimateScheduleRequest {
+    climate: Arc<Climate>
+}
+
+impl GetClimateScheduleRequest {
+    pub fn new(key: &str, climate: &Arc<Climate>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-climate-schedule")
+            .set_post(JsonMethodHandlerAdapter::new(GetClimateScheduleRequest {
+                climate: climate.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetClimateScheduleRequest {
+    type Input = Input;
+    type Output = ScheduleState;
+
+    async fn process(&self, _parts: Parts, _input: Input) -> Result<ScheduleState, ServerError> {
+        self.climate.schedule().state()
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
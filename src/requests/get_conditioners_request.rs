@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::config::ConditionerConfig;
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::{ServerError};
+use crate::services::climate::Climate;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    conditioners: Vec<ConditionerConfig>
+}
+
+pub struct GetConditionersRequest {
+    climate: Arc<Climate>
+}
+
+impl GetConditionersRequest {
+    pub fn new(key: &str, climate: &Arc<Climate>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-conditioners")
+            .set_post(JsonMethodHandlerAdapter::new(GetConditionersRequest {
+                climate: climate.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetConditionersRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, _input: Input) -> Result<Output, ServerError> {
+        Ok(Output {
+            conditioners: self.climate.inventory().to_vec()
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
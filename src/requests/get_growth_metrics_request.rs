@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::timelapse::{FrameMetrics, Timelapse};
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    pose: Option<String>,
+    since: Option<u64>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    frames: Vec<FrameMetrics>
+}
+
+pub struct GetGrowthMetricsRequest {
+    timelapse: Arc<Timelapse>
+}
+
+impl GetGrowthMetricsRequest {
+    pub fn new(key: &str, timelapse: &Arc<Timelapse>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-growth-metrics")
+            .set_post(JsonMethodHandlerAdapter::new(GetGrowthMetricsRequest {
+                timelapse: timelapse.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetGrowthMetricsRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        let timelapse = self.timelapse.clone();
+        Ok(Output {
+            frames: tokio::task::spawn_blocking(move || timelapse.metrics(input.pose.as_deref(), input.since)).await??
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use base64::engine::{Engine, general_purpose};
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::timelapse::Timelapse;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    pose: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    time: u64,
+    sequence: u32,
+    image_base64: String
+}
+
+/// Returns the last time-lapse frame without waking the camera.
+pub struct GetLatestFrameRequest {
+    timelapse: Arc<Timelapse>
+}
+
+impl GetLatestFrameRequest {
+    pub fn new(key: &str, timelapse: &Arc<Timelapse>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-latest-frame")
+            .set_post(JsonMethodHandlerAdapter::new(GetLatestFrameRequest {
+                timelapse: timelapse.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetLatestFrameRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        let timelapse = self.timelapse.clone();
+        let (info, frame) = tokio::task::spawn_blocking(move || timelapse.latest(input.pose.as_deref())).await??;
+        Ok(Output {
+            time: info.time,
+            sequence: info.sequence,
+            image_base64: general_purpose::STANDARD.encode(frame)
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
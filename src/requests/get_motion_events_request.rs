@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use base64::engine::{Engine, general_purpose};
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::motion::MotionDetector;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    since: Option<u64>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Event {
+    time: u64,
+    change: f32,
+    thumbnail_base64: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    events: Vec<Event>
+}
+
+pub struct GetMotionEventsRequest {
+    motion: Arc<MotionDetector>
+}
+
+impl GetMotionEventsRequest {
+    pub fn new(key: &str, motion: &Arc<MotionDetector>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-motion-events")
+            .set_post(JsonMethodHandlerAdapter::new(GetMotionEventsRequest {
+                motion: motion.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetMotionEventsRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        let events = self.motion.events(input.since)?
+            .into_iter()
+            .map(|e| Event {
+                time: e.time,
+                change: e.change,
+                thumbnail_base64: self.motion.thumbnail(e.time)
+                    .ok()
+                    .map(|t| general_purpose::STANDARD.encode(t))
+            })
+            .collect();
+
+        Ok(Output {
+            events
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::{ServerError};
+use crate::services::plants::{Plants, PlantStatus};
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    plants: Vec<PlantStatus>
+}
+
+pub struct GetPlantsRequest {
+    plants: Arc<Plants>
+}
+
+impl GetPlantsRequest {
+    pub fn new(key: &str, plants: &Arc<Plants>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-plants")
+            .set_post(JsonMethodHandlerAdapter::new(GetPlantsRequest {
+                plants: plants.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetPlantsRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, _input: Input) -> Result<Output, ServerError> {
+        Ok(Output {
+            plants: self.plants.statuses()?
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
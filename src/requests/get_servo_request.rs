@@ -0,0 +1,44 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::servos::{ServoControl, ServoStatus};
+
+use serde::Deserialize;
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    servo_id: Option<String>
+}
+
+pub struct GetServoRequest {
+    servo: Arc<ServoControl>
+}
+
+impl GetServoRequest {
+    pub fn new(key: &str, servo: &Arc<ServoControl>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-servo")
+            .set_post(JsonMethodHandlerAdapter::new(GetServoRequest {
+                servo: servo.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetServoRequest {
+    type Input = Input;
+    type Output = ServoStatus;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<ServoStatus, ServerError> {
+        self.servo.status(input.servo_id.as_deref())
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::{ServerError};
+use crate::services::soil::{Soil, SoilMoisture};
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    sensor_id: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    sensors: Vec<SoilMoisture>
+}
+
+pub struct GetSoilMoistureRequest {
+    soil: Arc<Soil>
+}
+
+impl GetSoilMoistureRequest {
+    pub fn new(key: &str, soil: &Arc<Soil>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-soil-moisture")
+            .set_post(JsonMethodHandlerAdapter::new(GetSoilMoistureRequest {
+                soil: soil.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetSoilMoistureRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        let sensors = match &input.sensor_id {
+            Some(id) => vec![self.soil.moisture(id)?],
+            None => self.soil.all()?
+        };
+
+        Ok(Output {
+            sensors
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use base64::engine::{Engine, general_purpose};
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::timelapse::Timelapse;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    pose: Option<String>,
+    time: u64,
+    /// Frames captured within the same second are told apart by the sequence.
+    #[serde(default)]
+    sequence: u32
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    time: u64,
+    sequence: u32,
+    image_base64: String
+}
+
+pub struct GetTimelapseFrameRequest {
+    timelapse: Arc<Timelapse>
+}
+
+impl GetTimelapseFrameRequest {
+    pub fn new(key: &str, timelapse: &Arc<Timelapse>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-timelapse-frame")
+            .set_post(JsonMethodHandlerAdapter::new(GetTimelapseFrameRequest {
+                timelapse: timelapse.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetTimelapseFrameRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        let timelapse = self.timelapse.clone();
+        let (pose, time, sequence) = (input.pose, input.time, input.sequence);
+        let frame = tokio::task::spawn_blocking(move || timelapse.frame(pose.as_deref(), time, sequence)).await??;
+        Ok(Output {
+            time,
+            sequence,
+            image_base64: general_purpose::STANDARD.encode(frame)
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
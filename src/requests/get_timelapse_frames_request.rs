@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::timelapse::{FrameInfo, Timelapse};
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    frames: Vec<FrameInfo>
+}
+
+pub struct GetTimelapseFramesRequest {
+    timelapse: Arc<Timelapse>
+}
+
+impl GetTimelapseFramesRequest {
+    pub fn new(key: &str, timelapse: &Arc<Timelapse>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-timelapse-frames")
+            .set_post(JsonMethodHandlerAdapter::new(GetTimelapseFramesRequest {
+                timelapse: timelapse.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetTimelapseFramesRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, _input: Input) -> Result<Output, ServerError> {
+        let timelapse = self.timelapse.clone();
+        Ok(Output {
+            frames: tokio::task::spawn_blocking(move || timelapse.frames()).await??
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
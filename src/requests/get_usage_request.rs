@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::usage::{Usage, UsagePeriod, UsageReport};
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+const DEFAULT_COUNT : usize = 7;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    #[serde(default)]
+    period: UsagePeriod,
+    count: Option<usize>
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    usage: Vec<UsageReport>
+}
+
+pub struct GetUsageRequest {
+    usage: Arc<Usage>
+}
+
+impl GetUsageRequest {
+    pub fn new(key: &str, usage: &Arc<Usage>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("get-usage")
+            .set_post(JsonMethodHandlerAdapter::new(GetUsageRequest {
+                usage: usage.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for GetUsageRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        Ok(Output {
+            usage: self.usage.report(input.period, input.count.unwrap_or(DEFAULT_COUNT))?
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Parts;
+use hyper::{Body, Response, StatusCode};
+
+use crate::server::query;
+use crate::server::request_handler::{MethodHandler, RequestHandler};
+use crate::server::server_error::ServerError;
+use crate::services::health::Health;
+
+/// Status of every subsystem, 503 if any check fails. Uptime monitors can call it without the protected key
+/// and get only the overall status, check details are returned with the key.
+pub struct HealthRequest {
+    health: Arc<Health>,
+    key: String
+}
+
+impl HealthRequest {
+    pub fn new(key: &str, health: &Arc<Health>) -> Arc<RequestHandler> {
+        Arc::new(RequestHandler::new("health")
+            .set_get(HealthRequest {
+                health: health.clone(),
+                key: key.to_string()
+            }))
+    }
+}
+
+#[async_trait]
+impl MethodHandler for HealthRequest {
+    async fn process(&self, parts: Parts, _data: Bytes) -> Result<Response<Body>, ServerError> {
+        // state storage is checked with a file write
+        let health = self.health.clone();
+        let report = tokio::task::spawn_blocking(move || health.report()).await??;
+
+        let status = match report.is_ok() {
+            true => StatusCode::OK,
+            false => StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        let body = match query::check_key(&parts, &self.key) {
+            Ok(()) => serde_json::to_vec(&report)?,
+            Err(_) => serde_json::to_vec(&report.summary())?
+        };
+
+        Ok(Response::builder()
+            .status(status)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))?)
+    }
+}
\ No newline at end of file
@@ -4,14 +4,15 @@ use hyper::http::request::Parts;
 
 use crate::server::request_handler::RequestHandler;
 use crate::server::server_error::{ServerError};
-use crate::utils::water_sensor::WaterSensor;
+use crate::services::water::Water;
 
 use serde::{Deserialize, Serialize};
 use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
 
 #[derive(Deserialize, Debug, Default)]
 pub struct Input {
-    key: String
+    key: String,
+    sensor_id: Option<String>
 }
 
 #[derive(Serialize, Debug)]
@@ -20,15 +21,15 @@ pub struct Output {
 }
 
 pub struct IsEnoughWaterRequest {
-    water_sensor: Arc<WaterSensor>
+    water: Arc<Water>
 }
 
 impl IsEnoughWaterRequest {
-    pub fn new(key: &str, water_sensor: &Arc<WaterSensor>) -> Arc<RequestHandler> {
+    pub fn new(key: &str, water: &Arc<Water>) -> Arc<RequestHandler> {
         let key = Some(key.to_string());
         Arc::new(RequestHandler::new("is-enough-water")
             .set_post(JsonMethodHandlerAdapter::new(IsEnoughWaterRequest {
-                water_sensor: water_sensor.clone()
+                water: water.clone()
             }, key)))
     }
 }
@@ -38,9 +39,9 @@ impl JsonMethodHandler for IsEnoughWaterRequest {
     type Input = Input;
     type Output = Output;
 
-    async fn process(&self, _: Parts, _: Input) -> Result<Output, ServerError> {
+    async fn process(&self, _: Parts, input: Input) -> Result<Output, ServerError> {
         Ok(Output {
-            result: self.water_sensor.is_enough()?
+            result: self.water.is_enough(input.sensor_id.as_deref())?
         })
     }
 
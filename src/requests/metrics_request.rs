@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Parts;
+use hyper::{Body, Response, StatusCode};
+
+use crate::server::query;
+use crate::server::request_handler::{MethodHandler, RequestHandler};
+use crate::server::server_error::ServerError;
+use crate::services::climate::Climate;
+use crate::services::metrics::Metrics;
+use crate::services::switches::Switches;
+use crate::services::water::Water;
+use crate::utils::prometheus::Exposition;
+
+/// Metrics in Prometheus text format, the key can be passed as `key` query parameter from the scrape config.
+pub struct MetricsRequest {
+    sources: Sources,
+    key: String
+}
+
+struct Sources {
+    metrics: Arc<Metrics>,
+    water: Arc<Water>,
+    climate: Arc<Climate>,
+    switches: Arc<Switches>
+}
+
+impl MetricsRequest {
+    pub fn new(key: &str, metrics: &Arc<Metrics>, water: &Arc<Water>, climate: &Arc<Climate>, switches: &Arc<Switches>) -> Arc<RequestHandler> {
+        Arc::new(RequestHandler::new("metrics")
+            .set_get(MetricsRequest {
+                sources: Sources {
+                    metrics: metrics.clone(),
+                    water: water.clone(),
+                    climate: climate.clone(),
+                    switches: switches.clone()
+                },
+                key: key.to_string()
+            }))
+    }
+}
+
+impl Sources {
+    fn collect(&self) -> Result<String, ServerError> {
+        let mut out = Exposition::new();
+        self.metrics.write(&mut out)?;
+
+        let pumps = self.water.pump_stats();
+        out.family("rpi_home_pump_runs_total", "counter", "Water pump runs since the server start.");
+        for pump in &pumps {
+            out.sample("rpi_home_pump_runs_total", &[("pump", &pump.id)], pump.runs as f64);
+        }
+        out.family("rpi_home_pump_seconds_total", "counter", "Water pump running time since the server start.");
+        for pump in &pumps {
+            out.sample("rpi_home_pump_seconds_total", &[("pump", &pump.id)], pump.seconds);
+        }
+        out.family("rpi_home_pump_running", "gauge", "Whether the water pump is running now.");
+        for pump in &pumps {
+            out.sample("rpi_home_pump_running", &[("pump", &pump.id)], bool_value(pump.running));
+        }
+
+        out.family("rpi_home_water_enough", "gauge", "Whether the water sensor detected enough water on the last read.");
+        for (id, level) in self.water.water_levels()? {
+            if let Some(enough) = level {
+                out.sample("rpi_home_water_enough", &[("sensor", &id)], bool_value(enough));
+            }
+        }
+
+        let sensors = self.climate.sensors()?;
+        out.family("rpi_home_room_temperature_celsius", "gauge", "Last temperature reading of the room.");
+        for (room, temperature) in sensors.room_temperatures() {
+            out.sample("rpi_home_room_temperature_celsius", &[("room", room)], temperature as f64);
+        }
+
+        out.family("rpi_home_weather_temperature_celsius", "gauge", "Last temperature reading of the weather sensor channel.");
+        for s in sensors.weather_sensors() {
+            out.sample("rpi_home_weather_temperature_celsius", &[("channel", &s.channel().to_string())], s.temperature() as f64);
+        }
+        out.family("rpi_home_weather_humidity_percent", "gauge", "Last humidity reading of the weather sensor channel.");
+        for s in sensors.weather_sensors() {
+            if let Some(humidity) = s.humidity() {
+                out.sample("rpi_home_weather_humidity_percent", &[("channel", &s.channel().to_string())], humidity as f64);
+            }
+        }
+        out.family("rpi_home_weather_low_battery", "gauge", "Whether the weather sensor reports low battery.");
+        for s in sensors.weather_sensors() {
+            out.sample("rpi_home_weather_low_battery", &[("channel", &s.channel().to_string())], bool_value(s.low_battery()));
+        }
+
+        out.family("rpi_home_switch_enabled", "gauge", "Whether the switch is enabled.");
+        for (name, enabled) in self.switches.states()? {
+            out.sample("rpi_home_switch_enabled", &[("switch", &name)], bool_value(enabled));
+        }
+
+        Ok(out.into_string())
+    }
+}
+
+#[async_trait]
+impl MethodHandler for MetricsRequest {
+    async fn process(&self, parts: Parts, _data: Bytes) -> Result<Response<Body>, ServerError> {
+        query::check_key(&parts, &self.key)?;
+
+        let text = self.sources.collect()?;
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+            .body(Body::from(text))?)
+    }
+}
+
+fn bool_value(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
\ No newline at end of file
@@ -7,4 +7,27 @@ pub mod conditioners_request;
 pub mod get_climate_request;
 pub mod set_climate_request;
 pub mod is_enabled_request;
-pub mod set_switch_request;
\ No newline at end of file
+pub mod set_switch_request;
+pub mod get_plants_request;
+pub mod calibrate_pump_request;
+pub mod get_soil_moisture_request;
+pub mod get_conditioners_request;
+pub mod get_climate_schedule_request;
+pub mod set_climate_programs_request;
+pub mod set_climate_mode_request;
+pub mod get_usage_request;
+pub mod get_timelapse_frames_request;
+pub mod get_timelapse_frame_request;
+pub mod get_latest_frame_request;
+pub mod camera_jpeg_request;
+pub mod camera_stream_request;
+pub mod get_growth_metrics_request;
+pub mod get_motion_events_request;
+pub mod motion_snapshot_request;
+pub mod get_servo_request;
+pub mod servo_preset_request;
+pub mod calibrate_servo_request;
+pub mod move_camera_request;
+pub mod metrics_request;
+pub mod health_request;
+pub mod ready_request;
\ No newline at end of file
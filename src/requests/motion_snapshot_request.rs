@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::body::Bytes;
+use hyper::header::{CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::http::request::Parts;
+use hyper::{Body, Response, StatusCode};
+
+use crate::server::query;
+use crate::server::request_handler::{MethodHandler, RequestHandler};
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::motion::MotionDetector;
+
+/// Returns snapshot of the motion event as `image/jpeg`, the event is set by the `time` query parameter.
+pub struct MotionSnapshotRequest {
+    motion: Arc<MotionDetector>,
+    key: String,
+    view_key: Option<String>
+}
+
+impl MotionSnapshotRequest {
+    pub fn new(key: &str, view_key: &Option<String>, motion: &Arc<MotionDetector>) -> Arc<RequestHandler> {
+        Arc::new(RequestHandler::new("motion-snapshot")
+            .set_get(MotionSnapshotRequest {
+                motion: motion.clone(),
+                key: key.to_string(),
+                view_key: view_key.clone()
+            }))
+    }
+}
+
+#[async_trait]
+impl MethodHandler for MotionSnapshotRequest {
+    async fn process(&self, parts: Parts, _data: Bytes) -> Result<Response<Body>, ServerError> {
+        query::check_view_key(&parts, &self.key, &self.view_key)?;
+
+        let time : u64 = query::param(&parts, "time")
+            .and_then(|t| t.parse().ok())
+            .ok_or(LogicError::MotionSnapshotNotFound)?;
+
+        let motion = self.motion.clone();
+        let data = tokio::task::spawn_blocking(move || motion.snapshot(time)).await??;
+
+        // snapshot of the event never changes
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "image/jpeg")
+            .header(CONTENT_LENGTH, data.len())
+            .header(CACHE_CONTROL, "private, max-age=86400")
+            .body(Body::from(data))?)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use base64::engine::{Engine, general_purpose};
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::camera_rig::CameraRig;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    /// Named pose from config, overrides `pan` and `tilt`.
+    pose: Option<String>,
+    pan: Option<f32>,
+    tilt: Option<f32>,
+    #[serde(default)]
+    capture: bool
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    result: String,
+    image_base64: Option<String>
+}
+
+pub struct MoveCameraRequest {
+    rig: Arc<CameraRig>
+}
+
+impl MoveCameraRequest {
+    pub fn new(key: &str, rig: &Arc<CameraRig>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("move-camera")
+            .set_post(JsonMethodHandlerAdapter::new(MoveCameraRequest {
+                rig: rig.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for MoveCameraRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        let photo = match &input.pose {
+            Some(pose) => CameraRig::move_to_pose(&self.rig, pose, input.capture).await?,
+            None => CameraRig::move_to(&self.rig, input.pan, input.tilt, input.capture).await?
+        };
+
+        Ok(Output {
+            result: "Success".to_owned(),
+            image_base64: photo.map(|p| general_purpose::STANDARD.encode(p))
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
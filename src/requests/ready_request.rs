@@ -0,0 +1,52 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Parts;
+use hyper::{Body, Response, StatusCode};
+
+use crate::server::query;
+use crate::server::request_handler::{MethodHandler, RequestHandler};
+use crate::server::server_error::ServerError;
+use crate::services::health::Health;
+
+/// Readiness for load balancers and monitors, 503 during startup, shutdown or when a critical subsystem fails.
+/// Check details are returned only with the protected key.
+pub struct ReadyRequest {
+    health: Arc<Health>,
+    key: String
+}
+
+impl ReadyRequest {
+    pub fn new(key: &str, health: &Arc<Health>) -> Arc<RequestHandler> {
+        Arc::new(RequestHandler::new("ready")
+            .set_get(ReadyRequest {
+                health: health.clone(),
+                key: key.to_string()
+            }))
+    }
+}
+
+#[async_trait]
+impl MethodHandler for ReadyRequest {
+    async fn process(&self, parts: Parts, _data: Bytes) -> Result<Response<Body>, ServerError> {
+        // state storage is checked with a file write
+        let health = self.health.clone();
+        let report = tokio::task::spawn_blocking(move || health.readiness()).await?;
+
+        let status = match report.is_ok() {
+            true => StatusCode::OK,
+            false => StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        let body = match query::check_key(&parts, &self.key) {
+            Ok(()) => serde_json::to_vec(&report)?,
+            Err(_) => serde_json::to_vec(&report.summary())?
+        };
+
+        Ok(Response::builder()
+            .status(status)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))?)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::servos::ServoControl;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct SaveInput {
+    key: Option<String>,
+    servo_id: Option<String>,
+    name: String,
+    /// Current position is saved if not set.
+    angle: Option<f32>
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct RemoveInput {
+    key: Option<String>,
+    servo_id: Option<String>,
+    name: String
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    result: String
+}
+
+/// POST saves the preset, DELETE removes it.
+pub struct ServoPresetRequest;
+
+struct SavePreset {
+    servo: Arc<ServoControl>
+}
+
+struct RemovePreset {
+    servo: Arc<ServoControl>
+}
+
+impl ServoPresetRequest {
+    pub fn new(key: &str, servo: &Arc<ServoControl>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("servo-preset")
+            .set_post(JsonMethodHandlerAdapter::new(SavePreset {
+                servo: servo.clone()
+            }, key.clone()))
+            .set_delete(JsonMethodHandlerAdapter::new(RemovePreset {
+                servo: servo.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for SavePreset {
+    type Input = SaveInput;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: SaveInput) -> Result<Output, ServerError> {
+        self.servo.save_preset(input.servo_id.as_deref(), &input.name, input.angle)?;
+        Ok(Output {
+            result: "Success".to_owned()
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a SaveInput) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for RemovePreset {
+    type Input = RemoveInput;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: RemoveInput) -> Result<Output, ServerError> {
+        self.servo.remove_preset(input.servo_id.as_deref(), &input.name)?;
+        Ok(Output {
+            result: "Success".to_owned()
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a RemoveInput) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
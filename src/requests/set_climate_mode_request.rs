@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use hyper::http::request::Parts;
+
+use crate::server::request_handler::RequestHandler;
+use crate::server::server_error::ServerError;
+use crate::services::climate::Climate;
+use crate::services::climate_schedule::ClimateMode;
+
+use serde::{Deserialize, Serialize};
+use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Input {
+    key: Option<String>,
+    mode: ClimateMode
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    result: String
+}
+
+pub struct SetClimateModeRequest {
+    climate: Arc<Climate>
+}
+
+impl SetClimateModeRequest {
+    pub fn new(key: &str, climate: &Arc<Climate>) -> Arc<RequestHandler> {
+        let key = Some(key.to_string());
+        Arc::new(RequestHandler::new("set-climate-mode")
+            .set_post(JsonMethodHandlerAdapter::new(SetClimateModeRequest {
+                climate: climate.clone()
+            }, key)))
+    }
+}
+
+#[async_trait]
+impl JsonMethodHandler for SetClimateModeRequest {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
+        self.climate.set_mode(input.mode)?;
+        Ok(Output {
+            result: "Success".to_owned()
+        })
+    }
+
+    fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
+        input.key.as_deref()
+    }
+}
\ No newline at end of file
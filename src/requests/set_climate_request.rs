@@ -4,7 +4,7 @@ use hyper::http::request::Parts;
 
 use crate::server::request_handler::RequestHandler;
 use crate::server::server_error::{ServerError};
-use crate::services::climate::{Conditioner, Climate};
+use crate::services::climate::{ConditionerSettings, Climate};
 
 use serde::{Deserialize, Serialize};
 use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
@@ -12,7 +12,7 @@ use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAd
 #[derive(Deserialize, Debug, Default)]
 pub struct Input {
     key: Option<String>,
-    conditioners: Vec<Conditioner>
+    conditioners: Vec<ConditionerSettings>
 }
 
 #[derive(Serialize, Debug)]
@@ -6,6 +6,7 @@ use crate::commands::command::Command;
 use crate::server::request_handler::RequestHandler;
 use crate::server::server_error::{ServerError};
 use crate::Switches;
+use crate::services::metrics::Metrics;
 
 use serde::{Deserialize, Serialize};
 use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
@@ -13,14 +14,15 @@ use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAd
 pub struct SwitchRequest;
 
 impl SwitchRequest {
-    pub fn new(key: &str, switches: &Arc<Switches>) -> Arc<RequestHandler> {
+    pub fn new(key: &str, switches: &Arc<Switches>, metrics: &Arc<Metrics>) -> Arc<RequestHandler> {
         let key = Some(key.to_string());
         Arc::new(RequestHandler::new("set-switch")
             .set_get(JsonMethodHandlerAdapter::new(GetSwitchMethod {
                 switches: switches.clone()
             }, key.clone()))
             .set_post(JsonMethodHandlerAdapter::new(PostSwitchMethod {
-                switches: switches.clone()
+                switches: switches.clone(),
+                metrics: metrics.clone()
             }, key.clone())))
     }
 }
@@ -74,7 +76,8 @@ pub struct EnableCommandOutput {
 }
 
 pub struct PostSwitchMethod {
-    switches: Arc<Switches>
+    switches: Arc<Switches>,
+    metrics: Arc<Metrics>
 }
 
 #[async_trait]
@@ -87,11 +90,19 @@ impl JsonMethodHandler for PostSwitchMethod {
 
         if let Some(ip) = ip {
             if let Some(port) = port {
-                let r = Command::new((ip, port))?
-                    .method_id(0)
-                    .input(EnableCommandInput { enabled: input.value })?
-                    .execute::<EnableCommandOutput>();
-
+                let command = Command::new((ip, port))
+                    .and_then(|c| c.method_id(0).input(EnableCommandInput { enabled: input.value }));
+
+                let command = match command {
+                    Ok(c) => c,
+                    Err(e) => {
+                        self.metrics.record_command(&input.name, false)?;
+                        return Err(e);
+                    }
+                };
+
+                let r = command.execute::<EnableCommandOutput>();
+                self.metrics.record_command(&input.name, r.is_ok())?;
                 if let Err(e) = r {
                     error!("error on switch command: {}", &e)
                 }
@@ -3,8 +3,8 @@ use async_trait::async_trait;
 use hyper::http::request::Parts;
 
 use crate::server::request_handler::RequestHandler;
-use crate::server::server_error::{ServerError};
-use crate::utils::servo::Servo;
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::servos::ServoControl;
 
 use serde::{Deserialize, Serialize};
 use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAdapter};
@@ -12,7 +12,9 @@ use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAd
 #[derive(Deserialize, Debug, Default)]
 pub struct Input {
     key: String,
-    angle: f32
+    servo_id: Option<String>,
+    angle: Option<f32>,
+    preset: Option<String>
 }
 
 #[derive(Serialize, Debug)]
@@ -21,11 +23,11 @@ pub struct Output {
 }
 
 pub struct TurnServoRequest {
-    servo: Arc<Servo>
+    servo: Arc<ServoControl>
 }
 
 impl TurnServoRequest {
-    pub fn new(key: &str, servo: &Arc<Servo>) -> Arc<RequestHandler> {
+    pub fn new(key: &str, servo: &Arc<ServoControl>) -> Arc<RequestHandler> {
         let key = Some(key.to_string());
         Arc::new(RequestHandler::new("turn-servo")
             .set_post(JsonMethodHandlerAdapter::new(TurnServoRequest {
@@ -40,7 +42,13 @@ impl JsonMethodHandler for TurnServoRequest {
     type Output = Output;
 
     async fn process(&self, _parts: Parts, input: Input) -> Result<Output, ServerError> {
-        self.servo.turn_to(input.angle)?;
+        let servo = self.servo.clone();
+        tokio::task::spawn_blocking(move || match (input.preset, input.angle) {
+            (Some(preset), _) => servo.move_to_preset(input.servo_id.as_deref(), &preset),
+            (None, Some(angle)) => servo.move_to(input.servo_id.as_deref(), angle),
+            (None, None) => Err(LogicError::InvalidServoInput.into())
+        }).await??;
+
         Ok(Output {
             result: "Ok".to_string()
         })
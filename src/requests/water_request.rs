@@ -3,8 +3,8 @@ use async_trait::async_trait;
 
 use crate::server::request_handler::RequestHandler;
 use crate::server::server_error::{ServerError};
-use crate::utils::water_sensor::WaterSensor;
-use crate::utils::water_pump::WaterPump;
+use crate::services::plants::{Plants, WateringOutcome};
+use crate::services::water::Water;
 use std::time::Duration;
 use hyper::http::request::Parts;
 
@@ -14,7 +14,11 @@ use crate::server::json_request_handler::{JsonMethodHandler, JsonMethodHandlerAd
 #[derive(Deserialize, Debug, Default)]
 pub struct Input {
     key: String,
+    pump_id: Option<String>,
+    plant_id: Option<String>,
+    #[serde(default)]
     duration_seconds: u64,
+    volume_ml: Option<f32>,
     force: bool
 }
 
@@ -25,19 +29,64 @@ pub struct Output {
 }
 
 pub struct WaterRequest {
-    water_sensor: Arc<WaterSensor>,
-    water_pump: Arc<WaterPump>
+    water: Arc<Water>,
+    plants: Arc<Plants>
 }
 
 impl WaterRequest {
-    pub fn new(key: &str, water_sensor: &Arc<WaterSensor>, water_pump: &Arc<WaterPump>) -> Arc<RequestHandler> {
+    pub fn new(key: &str, water: &Arc<Water>, plants: &Arc<Plants>) -> Arc<RequestHandler> {
         let key = Some(key.to_string());
         Arc::new(RequestHandler::new("water")
             .set_post(JsonMethodHandlerAdapter::new(WaterRequest {
-                water_sensor: water_sensor.clone(),
-                water_pump: water_pump.clone()
+                water: water.clone(),
+                plants: plants.clone()
             }, key)))
     }
+
+    fn watered() -> Result<Output, ServerError> {
+        Ok(Output {
+            result: true,
+            message: "Plant was watered".to_owned()
+        })
+    }
+
+    fn not_watered(message: String) -> Result<Output, ServerError> {
+        Ok(Output {
+            result: false,
+            message
+        })
+    }
+
+    fn water(water: &Water, plants: &Plants, i: Input) -> Result<Output, ServerError> {
+        if let Some(plant_id) = &i.plant_id {
+            return match plants.water(plant_id, i.volume_ml, i.force)? {
+                WateringOutcome::Watered(_) => WaterRequest::watered(),
+                WateringOutcome::NotEnoughWater => WaterRequest::not_watered("Not enough water".to_owned()),
+                WateringOutcome::TooFrequent(wait) => WaterRequest::not_watered(format!("Plant was watered recently, next watering in {}s", wait)),
+                WateringOutcome::DailyVolumeExceeded(left) => WaterRequest::not_watered(format!("Daily volume exceeded, {}ml left for today", left.max(0.0))),
+                WateringOutcome::MoistEnough(moisture) => WaterRequest::not_watered(format!("Soil is moist enough: {}%", moisture))
+            };
+        }
+
+        let pump_id = i.pump_id.as_deref();
+        let pump_plants = plants.plants_of_pump(pump_id)?;
+        if !i.force && !pump_plants.is_empty() {
+            return WaterRequest::not_watered(format!("Pump waters plants {}, water them by plant_id or set force", pump_plants.join(", ")));
+        }
+
+        let is_enough_water = water.is_enough_for_pump(pump_id)?;
+        if !i.force && !is_enough_water {
+            return WaterRequest::not_watered("Not enough water".to_owned());
+        }
+
+        let duration = match i.volume_ml {
+            Some(volume_ml) => water.duration_for_volume(pump_id, volume_ml)?.duration,
+            None => Duration::from_secs(i.duration_seconds)
+        };
+
+        water.enable_pump(pump_id, duration)?;
+        WaterRequest::watered()
+    }
 }
 
 #[async_trait]
@@ -46,23 +95,11 @@ impl JsonMethodHandler for WaterRequest {
     type Output = Output;
 
     async fn process(&self, _: Parts, i: Input) -> Result<Output, ServerError> {
-        info!("water request: duration {}s, force {}", &i.duration_seconds, &i.force);
-
-        let is_enough_water = self.water_sensor.is_enough()?;
-        if !i.force && !is_enough_water {
-            return Ok(Output {
-                result: false,
-                message: "Not enough water".to_owned()
-            });
-        }
+        info!("water request: pump {:?}, plant {:?}, duration {}s, volume {:?}ml, force {}", &i.pump_id, &i.plant_id, &i.duration_seconds, &i.volume_ml, &i.force);
 
-        let duration = Duration::from_secs(i.duration_seconds);
-        self.water_pump.enable(duration)?;
-
-        Ok(Output {
-            result: true,
-            message: "Plant was watered".to_owned()
-        })
+        // pump is enabled for the whole watering duration, so it should not hold an executor thread
+        let (water, plants) = (self.water.clone(), self.plants.clone());
+        tokio::task::spawn_blocking(move || WaterRequest::water(&water, &plants, i)).await?
     }
 
     fn read_key<'a>(&self, input: &'a Input) -> Option<&'a str> {
@@ -13,4 +13,8 @@ impl ErrorOutput {
             code: error as i32
         }
     }
+
+    pub fn code(&self) -> i32 {
+        self.code
+    }
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 use std::collections::HashMap;
 use std::convert::Infallible;
+use std::time::Instant;
 
 use hyper::{StatusCode, Request, Response, Body};
 
 use crate::server::error_output::ErrorOutput;
+use crate::services::metrics::Metrics;
 
 use self::request_handler::RequestHandler;
 use self::server_error::ServerError;
@@ -13,15 +15,21 @@ pub mod server_error;
 pub mod request_handler;
 pub mod json_request_handler;
 pub mod error_output;
+pub mod query;
 
 pub struct RpiHomeContext {
-    requests: HashMap<&'static str, Arc<RequestHandler>>
+    requests: HashMap<&'static str, Arc<RequestHandler>>,
+    metrics: Arc<Metrics>
 }
 
+/// Path label of requests to unknown handlers.
+const UNKNOWN_PATH : &str = "unknown";
+
 impl RpiHomeContext {
-    pub fn new() -> RpiHomeContext {
+    pub fn new(metrics: &Arc<Metrics>) -> RpiHomeContext {
         RpiHomeContext {
-            requests : HashMap::new()
+            requests : HashMap::new(),
+            metrics: metrics.clone()
         }
     }
 
@@ -31,13 +39,24 @@ impl RpiHomeContext {
     }
 
     pub async fn handle(context: Arc<RpiHomeContext>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let start = Instant::now();
+        let (path, response) = Self::respond(&context, req).await;
+
+        if let Err(e) = context.metrics.record_request(path, response.status(), start.elapsed()) {
+            error!("error on request metrics update: {}", e);
+        }
+
+        Ok(response)
+    }
+
+    async fn respond(context: &RpiHomeContext, req: Request<Body>) -> (&'static str, Response<Body>) {
         let (parts, body) = req.into_parts();
 
         let path = if let Some(header) = parts.headers.get("Server-Method") {
             if let Ok(h) = header.to_str() {
                 h.to_string()
             } else {
-                return Self::error_message("Not found", StatusCode::NOT_FOUND);
+                return (UNKNOWN_PATH, Self::error_message("Not found", StatusCode::NOT_FOUND));
             }
         } else {
             parts.uri.path()[1..]
@@ -47,52 +66,56 @@ impl RpiHomeContext {
                 .to_string()
         };
 
-        let data = match hyper::body::to_bytes(body).await {
-            Ok(d) => d,
-            Err(_) => return Self::error_message("Cannon read", StatusCode::BAD_REQUEST)
-        };
-
         let handler = {
             match context.requests.get(path.as_str()) {
                 Some(r) => r.clone(),
-                None => return Self::error_message("Not found", StatusCode::NOT_FOUND)
+                None => return (UNKNOWN_PATH, Self::error_message("Not found", StatusCode::NOT_FOUND))
             }
         };
 
+        let data = match hyper::body::to_bytes(body).await {
+            Ok(d) => d,
+            Err(_) => return (handler.path(), Self::error_message("Cannon read", StatusCode::BAD_REQUEST))
+        };
+
         let result = handler.process(parts, data).await;
 
-        Ok(match result {
+        let response = match result {
             Ok(r) => r,
             Err(ServerError::Logic(le)) => {
                 let output = ErrorOutput::new(le);
-                let output = match serde_json::to_vec(&output) {
-                    Ok(o) => o,
+                if let Err(e) = context.metrics.record_logic_error(output.code()) {
+                    error!("error on logic error metrics update: {}", e);
+                }
+
+                match serde_json::to_vec(&output) {
+                    Ok(o) => Self::error(Body::from(o), StatusCode::BAD_REQUEST),
                     Err(e) => {
                         error!("error on error serialization: {}", &e);
-                        return Self::error_message(e, StatusCode::INTERNAL_SERVER_ERROR)
+                        Self::error_message(e, StatusCode::INTERNAL_SERVER_ERROR)
                     }
-                };
-
-                return Self::error(Body::from(output), StatusCode::BAD_REQUEST)
+                }
             }
             Err(e) => {
                 error!("error on request process: {}", &e);
-                return Self::error_message(e, StatusCode::BAD_REQUEST)
+                Self::error_message(e, StatusCode::BAD_REQUEST)
             }
-        })
+        };
+
+        (handler.path(), response)
     }
 
-    fn error_message<T: ToString>(message: T, code: StatusCode) -> Result<Response<Body>, Infallible> {
+    fn error_message<T: ToString>(message: T, code: StatusCode) -> Response<Body> {
         let body = Body::from(message.to_string());
         Self::error(body, code)
     }
 
-    fn error(body: Body, code: StatusCode) -> Result<Response<Body>, Infallible> {
+    fn error(body: Body, code: StatusCode) -> Response<Body> {
         let r = Response::builder()
             .status(code)
             .body(body);
 
-        Ok(unwrap(r))
+        unwrap(r)
     }
 }
 
@@ -0,0 +1,128 @@
+use hyper::http::request::Parts;
+
+use crate::server::server_error::{LogicError, ServerError};
+
+const KEY_HEADER : &str = "Protected-Key";
+
+/// Returns percent-decoded value of the query parameter, `+` is decoded as a space.
+pub fn param(parts: &Parts, name: &str) -> Option<String> {
+    parts.uri.query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(n, _)| decode(n) == name)
+        .map(|(_, v)| decode(v))
+}
+
+/// Checks protected key of requests without json body. `Protected-Key` header is preferred,
+/// `key` query parameter is accepted for clients which cannot set headers, but the key in the URL
+/// is kept in browser history and proxy logs, so such clients should use the view key where possible.
+pub fn check_key(parts: &Parts, key: &str) -> Result<(), ServerError> {
+    if header_key(parts)? == Some(key) {
+        return Ok(());
+    }
+
+    if param(parts, "key").as_deref() == Some(key) {
+        return Ok(());
+    }
+
+    Err(LogicError::InvalidProtectedKey.into())
+}
+
+/// Checks key of image URLs, which are opened by `img` and `video` tags and cannot send headers.
+/// The query takes only the read-only view key if it is configured, the protected key is accepted in the header.
+pub fn check_view_key(parts: &Parts, key: &str, view_key: &Option<String>) -> Result<(), ServerError> {
+    let view_key = match view_key {
+        Some(k) => k,
+        None => return check_key(parts, key)
+    };
+
+    if header_key(parts)? == Some(key) {
+        return Ok(());
+    }
+
+    if param(parts, "key").as_deref() == Some(view_key.as_str()) {
+        return Ok(());
+    }
+
+    Err(LogicError::InvalidProtectedKey.into())
+}
+
+fn header_key(parts: &Parts) -> Result<Option<&str>, ServerError> {
+    match parts.headers.get(KEY_HEADER) {
+        Some(h) => Ok(Some(h.to_str()?)),
+        None => Ok(None)
+    }
+}
+
+/// Invalid escapes are kept as is, invalid UTF-8 is replaced.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes.get(i + 1..i + 3) {
+            Some([high, low]) if bytes[i] == b'%' => hex(*high).zip(hex(*low)),
+            _ => None
+        };
+
+        match (bytes[i], escaped) {
+            (_, Some((high, low))) => {
+                decoded.push(high << 4 | low);
+                i += 2;
+            },
+            (b'+', _) => decoded.push(b' '),
+            (b, _) => decoded.push(b)
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn hex(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::Request;
+
+    fn parts(uri: &str, header: Option<&str>) -> Parts {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(h) = header {
+            builder = builder.header(KEY_HEADER, h);
+        }
+        builder.body(()).unwrap().into_parts().0
+    }
+
+    #[test]
+    fn decodes_params() {
+        let parts = parts("/camera-image?key=a%2Bb%26c+d&width=100&bad=%zz%4", None);
+
+        assert_eq!(param(&parts, "key").as_deref(), Some("a+b&c d"));
+        assert_eq!(param(&parts, "width").as_deref(), Some("100"));
+        assert_eq!(param(&parts, "bad").as_deref(), Some("%zz%4"));
+        assert_eq!(param(&parts, "height"), None);
+    }
+
+    #[test]
+    fn checks_key_in_header_and_query() {
+        assert!(check_key(&parts("/metrics", Some("a+b")), "a+b").is_ok());
+        assert!(check_key(&parts("/metrics?key=wrong", Some("a+b")), "a+b").is_ok());
+        assert!(check_key(&parts("/metrics?key=a%2Bb", None), "a+b").is_ok());
+        assert!(check_key(&parts("/metrics?key=a+b", None), "a+b").is_err());
+        assert!(check_key(&parts("/metrics", None), "a+b").is_err());
+    }
+
+    #[test]
+    fn takes_only_view_key_in_query() {
+        let view_key = Some("view".to_owned());
+
+        assert!(check_view_key(&parts("/camera-stream?key=view", None), "secret", &view_key).is_ok());
+        assert!(check_view_key(&parts("/camera-stream", Some("secret")), "secret", &view_key).is_ok());
+        assert!(check_view_key(&parts("/camera-stream?key=secret", None), "secret", &view_key).is_err());
+        assert!(check_view_key(&parts("/camera-stream?key=secret", None), "secret", &None).is_ok());
+    }
+}
\ No newline at end of file
@@ -6,6 +6,7 @@ use hyper;
 use hyper::header::ToStrError;
 use serde_json;
 
+use crate::utils::adc::AdcError;
 use crate::utils::camera::CameraError;
 use crate::utils::rppal_error::RppalError;
 
@@ -23,6 +24,12 @@ pub enum ServerError {
     Camera(#[from] CameraError),
     #[error("Rppal error: {0}")]
     Rppal(#[from] RppalError),
+    #[error("Adc error: {0}")]
+    Adc(#[from] AdcError),
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("Blocking task error: {0}")]
+    Join(#[from] tokio::task::JoinError),
     #[error("To string error: {0}")]
     ToStr(#[from] ToStrError),
     #[error("Mutex is poison")]
@@ -42,7 +49,55 @@ pub enum LogicError {
     #[error("Command has unsupported content type")]
     CommandUnsupportedContentType = 5,
     #[error("Socket address not found")]
-    CommandSocketAddressNotFound = 6
+    CommandSocketAddressNotFound = 6,
+    #[error("Water pump was not found")]
+    WaterPumpNotFound = 7,
+    #[error("Water sensor was not found")]
+    WaterSensorNotFound = 8,
+    #[error("Plant was not found")]
+    PlantNotFound = 9,
+    #[error("Water pump flow rate is not calibrated")]
+    PumpNotCalibrated = 10,
+    #[error("Water pump calibration was not started")]
+    CalibrationNotStarted = 11,
+    #[error("Invalid calibration duration or volume")]
+    InvalidCalibration = 12,
+    #[error("Soil sensor was not found")]
+    SoilSensorNotFound = 13,
+    #[error("Adc is not configured")]
+    AdcNotConfigured = 14,
+    #[error("Conditioner was not found")]
+    ConditionerNotFound = 15,
+    #[error("Conditioner temperature is out of range")]
+    ConditionerTemperatureOutOfRange = 16,
+    #[error("Conditioner mode is not supported")]
+    ConditionerModeUnsupported = 17,
+    #[error("Climate program time is invalid")]
+    InvalidClimateProgramTime = 18,
+    #[error("Time-lapse frame was not found")]
+    FrameNotFound = 19,
+    #[error("Too many camera stream viewers")]
+    TooManyStreamViewers = 20,
+    #[error("Servo preset was not found")]
+    ServoPresetNotFound = 21,
+    #[error("Invalid servo calibration")]
+    InvalidServoCalibration = 22,
+    #[error("Servo was not found")]
+    ServoNotFound = 23,
+    #[error("Camera pose was not found")]
+    CameraPoseNotFound = 24,
+    #[error("Water pump runtime is longer than allowed")]
+    PumpRuntimeExceeded = 25,
+    #[error("Water volume is invalid")]
+    InvalidWaterVolume = 26,
+    #[error("Servo angle or preset is not set")]
+    InvalidServoInput = 27,
+    #[error("Water pump or sensor id is not unique")]
+    DuplicateWaterId = 28,
+    #[error("Conditioner is set more than once")]
+    DuplicateConditionerSettings = 29,
+    #[error("Motion snapshot was not found")]
+    MotionSnapshotNotFound = 30
 }
 
 impl<T> From<PoisonError<T>> for ServerError {
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+
+use crate::config::{CameraRigConfig, PoseConfig};
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::camera_service::{CameraService, Resolution};
+use crate::services::servos::ServoControl;
+
+/// Pan/tilt camera, photos at named poses let one camera watch several plants.
+pub struct CameraRig {
+    config: CameraRigConfig,
+    servos: Arc<ServoControl>,
+    camera: Arc<CameraService>,
+    /// Held from the move until the photo is taken, so photos are not taken at another request pose.
+    busy: Mutex<()>
+}
+
+impl CameraRig {
+    pub fn new(config: &CameraRigConfig, servos: &Arc<ServoControl>, camera: &Arc<CameraService>) -> Result<Self, ServerError> {
+        servos.check_servo(&config.pan_servo_id)?;
+        servos.check_servo(&config.tilt_servo_id)?;
+
+        Ok(CameraRig {
+            config: config.clone(),
+            servos: servos.clone(),
+            camera: camera.clone(),
+            busy: Mutex::new(())
+        })
+    }
+
+    pub fn check_pose(&self, name: &str) -> Result<(), ServerError> {
+        self.pose(name)?;
+        Ok(())
+    }
+
+    /// Moves servos which angles are set and takes a photo if `capture` is set.
+    pub async fn move_to(rig: &Arc<CameraRig>, pan: Option<f32>, tilt: Option<f32>, capture: bool) -> Result<Option<Vec<u8>>, ServerError> {
+        let _busy = rig.busy.lock().await;
+
+        let task_rig = rig.clone();
+        tokio::task::spawn_blocking(move || task_rig.turn(pan, tilt)).await??;
+
+        if !capture {
+            return Ok(None);
+        }
+
+        Ok(Some(rig.camera.capture(Resolution::Full).await?))
+    }
+
+    pub async fn move_to_pose(rig: &Arc<CameraRig>, name: &str, capture: bool) -> Result<Option<Vec<u8>>, ServerError> {
+        let pose = rig.pose(name)?;
+        CameraRig::move_to(rig, Some(pose.pan), Some(pose.tilt), capture).await
+    }
+
+    /// Moves to the pose and takes a photo there.
+    pub async fn capture_at(rig: &Arc<CameraRig>, name: &str) -> Result<Vec<u8>, ServerError> {
+        let photo = CameraRig::move_to_pose(rig, name, true).await?;
+        Ok(photo.unwrap_or_default())
+    }
+
+    fn turn(&self, pan: Option<f32>, tilt: Option<f32>) -> Result<(), ServerError> {
+        if let Some(pan) = pan {
+            self.servos.move_to(Some(&self.config.pan_servo_id), pan)?;
+        }
+        if let Some(tilt) = tilt {
+            self.servos.move_to(Some(&self.config.tilt_servo_id), tilt)?;
+        }
+
+        // rig should stop shaking before a photo
+        thread::sleep(Duration::from_millis(self.config.settle_millis));
+        Ok(())
+    }
+
+    fn pose(&self, name: &str) -> Result<PoseConfig, ServerError> {
+        Ok(*self.config.poses.get(name).ok_or(LogicError::CameraPoseNotFound)?)
+    }
+}
\ No newline at end of file
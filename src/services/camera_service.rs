@@ -0,0 +1,266 @@
+use std::sync::{Arc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot;
+
+use crate::config::CameraConfig;
+use crate::utils::camera::{ActiveCamera, Camera, CameraError};
+
+/// Preview captures return full frames for this time after a full capture,
+/// so alternating photos and motion frames do not reactivate the camera every time.
+const FULL_RESOLUTION_HOLD : Duration = Duration::from_secs(30);
+
+/// Frame size of a capture, preview frames are enough for analysis and are cheaper to capture and decode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolution {
+    Full,
+    Preview
+}
+
+enum Message {
+    Capture(Resolution, oneshot::Sender<Result<Vec<u8>, CameraError>>),
+    Release
+}
+
+/// Owns the camera on a dedicated thread and captures queued requests one by one.
+/// The camera stays active for the idle window after the last capture and while
+/// any lease is held, so following requests do not wait for warm-up.
+/// Changing the resolution reactivates the camera with warm-up.
+pub struct CameraService {
+    messages: Sender<Message>,
+    leases: Arc<AtomicUsize>,
+    status: Arc<Status>
+}
+
+/// Camera thread state for health checks, the camera is not touched to read it.
+struct Status {
+    running: AtomicBool,
+    last_error: Mutex<Option<String>>
+}
+
+/// Keeps the camera active until dropped.
+pub struct CameraLease {
+    messages: Sender<Message>,
+    leases: Arc<AtomicUsize>
+}
+
+impl CameraService {
+    pub fn start(config: &CameraConfig, camera: Camera) -> Result<Self, std::io::Error> {
+        let (sender, receiver) = mpsc::channel();
+        let leases = Arc::new(AtomicUsize::new(0));
+        let warm_up = Duration::from_millis(config.warm_up_millis);
+        let idle = Duration::from_secs(config.idle_seconds);
+        let preview = (config.preview_width, config.preview_height);
+
+        let status = Arc::new(Status {
+            running: AtomicBool::new(true),
+            last_error: Mutex::new(None)
+        });
+
+        let thread_leases = leases.clone();
+        let thread_status = status.clone();
+        thread::Builder::new()
+            .name("camera".to_owned())
+            .spawn(move || {
+                CameraService::run(camera, receiver, thread_leases, &thread_status, warm_up, idle, preview);
+                thread_status.running.store(false, Ordering::SeqCst);
+            })?;
+
+        Ok(CameraService {
+            messages: sender,
+            leases,
+            status
+        })
+    }
+
+    /// Fails if the camera thread is stopped or the last capture failed, it recovers on the next successful capture.
+    pub fn check(&self) -> Result<(), String> {
+        if !self.status.running.load(Ordering::SeqCst) {
+            return Err(CameraError::Stopped.to_string());
+        }
+
+        match self.status.last_error.lock().unwrap_or_else(PoisonError::into_inner).as_ref() {
+            Some(e) => Err(format!("last capture failed: {}", e)),
+            None => Ok(())
+        }
+    }
+
+    pub async fn capture(&self, resolution: Resolution) -> Result<Vec<u8>, CameraError> {
+        let (reply, result) = oneshot::channel();
+        self.messages.send(Message::Capture(resolution, reply)).map_err(|_| CameraError::Stopped)?;
+        result.await.map_err(|_| CameraError::Stopped)?
+    }
+
+    pub fn lease(&self) -> CameraLease {
+        self.leases.fetch_add(1, Ordering::SeqCst);
+        CameraLease {
+            messages: self.messages.clone(),
+            leases: self.leases.clone()
+        }
+    }
+
+    #[cfg(test)]
+    pub fn leases(&self) -> usize {
+        self.leases.load(Ordering::SeqCst)
+    }
+
+    fn run(camera: Camera, receiver: Receiver<Message>, leases: Arc<AtomicUsize>, status: &Status, warm_up: Duration, idle: Duration, preview: (u32, u32)) {
+        let mut active : Option<(ActiveCamera, Resolution)> = None;
+        let mut last_full : Option<Instant> = None;
+        let is_leased = || leases.load(Ordering::SeqCst) > 0;
+
+        loop {
+            let message = if active.is_some() && !idle.is_zero() {
+                match receiver.recv_timeout(idle) {
+                    Ok(m) => m,
+                    Err(RecvTimeoutError::Timeout) => {
+                        if !is_leased() {
+                            info!("camera is idle, deactivating");
+                            active = None;
+                        }
+                        continue;
+                    },
+                    Err(RecvTimeoutError::Disconnected) => break
+                }
+            } else {
+                match receiver.recv() {
+                    Ok(m) => m,
+                    Err(_) => break
+                }
+            };
+
+            let (resolution, reply) = match message {
+                Message::Capture(resolution, reply) => (resolution, reply),
+                Message::Release => {
+                    if idle.is_zero() && !is_leased() && active.is_some() {
+                        info!("camera is released, deactivating");
+                        active = None;
+                    }
+                    continue;
+                }
+            };
+
+            if resolution == Resolution::Full {
+                last_full = Some(Instant::now());
+            }
+
+            let reactivate = match (&active, resolution) {
+                (Some((_, Resolution::Preview)), Resolution::Full) => true,
+                (Some((_, Resolution::Full)), Resolution::Preview) => last_full.map(|t| t.elapsed() >= FULL_RESOLUTION_HOLD).unwrap_or(true),
+                _ => false
+            };
+            if reactivate {
+                info!("camera resolution is changed to {:?}, reactivating", resolution);
+                active = None;
+            }
+
+            let size = match resolution {
+                Resolution::Full => None,
+                Resolution::Preview => Some(preview)
+            };
+
+            let result = match active.as_mut() {
+                Some((a, _)) => a.make_photo(),
+                None => camera.activate(warm_up, size).and_then(|mut a| {
+                    let photo = a.make_photo();
+                    active = Some((a, resolution));
+                    photo
+                })
+            };
+
+            if result.is_err() {
+                // device could be in a bad state, it is reopened on the next request
+                active = None;
+            }
+            *status.last_error.lock().unwrap_or_else(PoisonError::into_inner) = result.as_ref().err().map(|e| e.to_string());
+
+            if idle.is_zero() && !is_leased() {
+                active = None;
+            }
+
+            let _ = reply.send(result);
+        }
+
+        info!("camera service is stopped");
+    }
+}
+
+impl Drop for CameraLease {
+    fn drop(&mut self) {
+        self.leases.fetch_sub(1, Ordering::SeqCst);
+        let _ = self.messages.send(Message::Release);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn simulated_camera() -> Arc<CameraService> {
+        let config = CameraConfig {
+            warm_up_millis: 0,
+            simulated: true,
+            ..CameraConfig::default()
+        };
+
+        Arc::new(CameraService::start(&config, Camera::Simulated).unwrap())
+    }
+
+    fn size(photo: &[u8]) -> (u32, u32) {
+        let image = image::load_from_memory_with_format(photo, image::ImageFormat::Jpeg).unwrap();
+        (image.width(), image.height())
+    }
+
+    #[tokio::test]
+    async fn captures_decodable_jpeg() {
+        let camera = simulated_camera();
+        let photo = camera.capture(Resolution::Full).await.unwrap();
+
+        assert_eq!(size(&photo), (640, 480));
+        assert!(camera.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn captures_preview_until_full_is_requested() {
+        let camera = simulated_camera();
+        let lease = camera.lease();
+
+        let preview = camera.capture(Resolution::Preview).await.unwrap();
+        let full = camera.capture(Resolution::Full).await.unwrap();
+        // camera is kept at full size right after a full capture
+        let held = camera.capture(Resolution::Preview).await.unwrap();
+        drop(lease);
+
+        assert_eq!(size(&preview), (320, 240));
+        assert_eq!(size(&full), (640, 480));
+        assert_eq!(size(&held), (640, 480));
+    }
+
+    #[tokio::test]
+    async fn captures_changing_frames() {
+        let camera = simulated_camera();
+        let lease = camera.lease();
+
+        let first = camera.capture(Resolution::Full).await.unwrap();
+        let second = camera.capture(Resolution::Full).await.unwrap();
+        drop(lease);
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn releases_lease_on_drop() {
+        let camera = simulated_camera();
+
+        let lease = camera.lease();
+        let other = camera.lease();
+        assert_eq!(camera.leases(), 2);
+
+        drop(lease);
+        drop(other);
+        assert_eq!(camera.leases(), 0);
+    }
+}
\ No newline at end of file
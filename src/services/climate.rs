@@ -1,18 +1,41 @@
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{Local, NaiveDateTime};
 use serde_repr::*;
-use crate::server::server_error::ServerError;
+use tokio::task::JoinHandle;
+use crate::config::{ClimateTarget, ConditionerConfig, StalenessConfig};
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::climate_schedule::{ClimateMode, ClimateProgram, ClimateSchedule};
+use crate::services::conditioner_remote::ConditionerRemote;
+use crate::services::shutdown::Shutdown;
+use crate::services::usage::Usage;
+use crate::utils::climate_sensor::ClimateReading;
+use crate::utils::ir::{IrCommand, IrMode};
+use crate::utils::rf433::WeatherReading;
+use crate::utils::time;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 pub struct Conditioner {
+    id: String,
+    enabled: bool,
+    controlled: bool,
+    temperature: i32,
+    mode: ConditionerMode
+}
+
+/// Conditioner settings sent by the client, conditioners without id are matched by index.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConditionerSettings {
+    id: Option<String>,
     enabled: bool,
     controlled: bool,
     temperature: i32,
     mode: ConditionerMode
 }
 
-#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(i32)]
 pub enum ConditionerMode {
     Auto = 0,
@@ -26,83 +49,460 @@ pub enum ConditionerMode {
 pub struct WeatherSensor {
     channel: i32,
     temperature: f32,
-    humidity: i32,
+    humidity: Option<i32>,
     low_battery: bool,
     time: Option<u64>
 }
 
 pub struct Climate {
+    staleness: StalenessConfig,
+    inventory: Vec<ConditionerConfig>,
+    schedule: Arc<ClimateSchedule>,
+    remote: Option<Arc<ConditionerRemote>>,
+    usage: Arc<Usage>,
     state: Mutex<State>
 }
 
 #[derive(Serialize, Debug, Clone)]
 pub struct Sensors {
     weather_sensors: Vec<WeatherSensor>,
-    sensor_temp: f32,
-    bedroom_temp: f32,
-    living_temp: f32
+    sensor_temp: Option<f32>,
+    bedroom_temp: Option<f32>,
+    living_temp: Option<f32>,
+    sensor_time: Option<u64>,
+    bedroom_time: Option<u64>,
+    living_time: Option<u64>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReadingHealth {
+    name: String,
+    time: Option<u64>,
+    age_seconds: Option<u64>,
+    stale: bool,
+    missing: bool
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SensorsHealth {
+    readings: Vec<ReadingHealth>,
+    low_battery_channels: Vec<i32>
 }
 
 struct State {
     conditioners: Vec<Conditioner>,
-    sensors: Sensors
+    sensors: Sensors,
+    local: Vec<LocalReading>,
+    radio: Vec<WeatherSensor>
+}
+
+/// Reading of the sensor connected to the raspberry pi, merged with values sent by clients.
+struct LocalReading {
+    id: String,
+    target: ClimateTarget,
+    channel: Option<i32>,
+    reading: ClimateReading,
+    time: u64
+}
+
+impl Conditioner {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn ir_command(&self) -> IrCommand {
+        IrCommand {
+            power: self.enabled,
+            mode: match self.mode {
+                ConditionerMode::Auto => IrMode::Auto,
+                ConditionerMode::Cool => IrMode::Cool,
+                ConditionerMode::Dry => IrMode::Dry,
+                ConditionerMode::Fan => IrMode::Fan,
+                ConditionerMode::Heat => IrMode::Heat
+            },
+            temperature: self.temperature
+        }
+    }
+}
+
+impl WeatherSensor {
+    pub fn channel(&self) -> i32 {
+        self.channel
+    }
+
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    pub fn humidity(&self) -> Option<i32> {
+        self.humidity
+    }
+
+    pub fn low_battery(&self) -> bool {
+        self.low_battery
+    }
 }
 
 impl Sensors {
     pub fn empty() -> Self {
         Sensors {
             weather_sensors: Vec::new(),
-            sensor_temp: 0.0,
-            bedroom_temp: 0.0,
-            living_temp: 0.0
+            sensor_temp: None,
+            bedroom_temp: None,
+            living_temp: None,
+            sensor_time: None,
+            bedroom_time: None,
+            living_time: None
         }
     }
 
-    pub fn new(weather_sensors: Vec<WeatherSensor>, sensor_temp: f32, bedroom_temp: f32, living_temp: f32) -> Self {
+    /// Creates sensors from values sent by the client, readings without time are stamped with current time,
+    /// rooms without value stay missing.
+    pub fn new(mut weather_sensors: Vec<WeatherSensor>, sensor_temp: Option<f32>, bedroom_temp: Option<f32>, living_temp: Option<f32>) -> Self {
+        let now = time::unix_now();
+        for sensor in weather_sensors.iter_mut() {
+            sensor.time = sensor.time.or(Some(now));
+        }
+
         Sensors {
             weather_sensors,
             sensor_temp,
             bedroom_temp,
-            living_temp
+            living_temp,
+            sensor_time: sensor_temp.map(|_| now),
+            bedroom_time: bedroom_temp.map(|_| now),
+            living_time: living_temp.map(|_| now)
+        }
+    }
+
+    /// Returns last temperature of every room which has a reading.
+    pub fn room_temperatures(&self) -> Vec<(&'static str, f32)> {
+        [("sensor", self.sensor_temp), ("bedroom", self.bedroom_temp), ("living", self.living_temp)]
+            .iter()
+            .filter_map(|(room, temperature)| temperature.map(|t| (*room, t)))
+            .collect()
+    }
+
+    pub fn weather_sensors(&self) -> &[WeatherSensor] {
+        &self.weather_sensors
+    }
+
+    /// Checks age of every reading and reports weather sensors with low battery.
+    pub fn health(&self, staleness: &StalenessConfig) -> SensorsHealth {
+        let now = time::unix_now();
+        let mut readings = vec![
+            ReadingHealth::new("sensor", self.sensor_time, staleness.room_max_age_seconds, now),
+            ReadingHealth::new("bedroom", self.bedroom_time, staleness.room_max_age_seconds, now),
+            ReadingHealth::new("living", self.living_time, staleness.room_max_age_seconds, now)
+        ];
+
+        for sensor in &self.weather_sensors {
+            let name = format!("weather:{}", sensor.channel);
+            readings.push(ReadingHealth::new(&name, sensor.time, staleness.weather_max_age_seconds, now));
+        }
+
+        for channel in &staleness.expected_weather_channels {
+            if !self.weather_sensors.iter().any(|s| s.channel == *channel) {
+                let name = format!("weather:{}", channel);
+                readings.push(ReadingHealth::new(&name, None, staleness.weather_max_age_seconds, now));
+            }
+        }
+
+        SensorsHealth {
+            readings,
+            low_battery_channels: self.weather_sensors
+                .iter()
+                .filter(|s| s.low_battery)
+                .map(|s| s.channel)
+                .collect()
+        }
+    }
+
+    fn apply_weather(&mut self, sensor: WeatherSensor) {
+        match self.weather_sensors.iter_mut().find(|s| s.channel == sensor.channel) {
+            Some(s) => {
+                if sensor.time.unwrap_or(0) >= s.time.unwrap_or(0) {
+                    *s = sensor
+                }
+            },
+            None => self.weather_sensors.push(sensor)
+        }
+    }
+
+    fn apply(&mut self, local: &LocalReading) {
+        let temperature = local.reading.temperature;
+        let (value, time) = match local.target {
+            ClimateTarget::Sensor => (&mut self.sensor_temp, &mut self.sensor_time),
+            ClimateTarget::Bedroom => (&mut self.bedroom_temp, &mut self.bedroom_time),
+            ClimateTarget::Living => (&mut self.living_temp, &mut self.living_time),
+            ClimateTarget::Weather => {
+                let channel = local.channel.unwrap_or(0);
+                let humidity = local.reading.humidity.map(|h| h.round() as i32);
+
+                self.apply_weather(WeatherSensor {
+                    channel,
+                    temperature,
+                    humidity,
+                    low_battery: false,
+                    time: Some(local.time)
+                });
+                return;
+            }
+        };
+
+        // Newest reading wins, so stale local sensor does not hide fresh client values.
+        if local.time >= time.unwrap_or(0) {
+            *value = Some(temperature);
+            *time = Some(local.time);
+        }
+    }
+}
+
+impl SensorsHealth {
+    /// Room reading can be used for control only if it is present and fresh.
+    pub fn is_usable(&self, room: ClimateTarget) -> bool {
+        let name = match room {
+            ClimateTarget::Sensor => "sensor",
+            ClimateTarget::Bedroom => "bedroom",
+            ClimateTarget::Living => "living",
+            ClimateTarget::Weather => return false
+        };
+
+        self.readings
+            .iter()
+            .any(|r| r.name == name && !r.stale && !r.missing)
+    }
+
+    /// Age of the most recent reading, `None` if nothing was received yet.
+    pub fn last_update_age(&self) -> Option<u64> {
+        self.readings.iter().filter_map(|r| r.age_seconds).min()
+    }
+
+    pub fn stale_readings(&self) -> Vec<&str> {
+        self.readings
+            .iter()
+            .filter(|r| r.stale)
+            .map(|r| r.name.as_str())
+            .collect()
+    }
+}
+
+impl ReadingHealth {
+    fn new(name: &str, time: Option<u64>, max_age_seconds: u64, now: u64) -> Self {
+        let age_seconds = time.map(|t| now.saturating_sub(t));
+        ReadingHealth {
+            name: name.to_string(),
+            time,
+            age_seconds,
+            stale: age_seconds.map(|a| a > max_age_seconds).unwrap_or(false),
+            missing: time.is_none()
         }
     }
 }
 
 impl Climate {
-    pub fn new() -> Self {
+    pub fn new(staleness: &StalenessConfig, inventory: &[ConditionerConfig], schedule: &Arc<ClimateSchedule>, remote: Option<Arc<ConditionerRemote>>, usage: &Arc<Usage>) -> Self {
         Climate {
-            state: Mutex::new(Climate::new_state())
+            staleness: staleness.clone(),
+            inventory: inventory.to_vec(),
+            schedule: schedule.clone(),
+            remote,
+            usage: usage.clone(),
+            state: Mutex::new(Climate::new_state(inventory))
         }
     }
 
-    fn new_state() -> State {
+    fn new_state(inventory: &[ConditionerConfig]) -> State {
         State {
-            conditioners: vec![Climate::new_conditioner(), Climate::new_conditioner()],
-            sensors: Sensors::empty()
+            conditioners: inventory.iter().map(Climate::new_conditioner).collect(),
+            sensors: Sensors::empty(),
+            local: Vec::new(),
+            radio: Vec::new()
         }
     }
 
-    fn new_conditioner() -> Conditioner {
+    fn new_conditioner(config: &ConditionerConfig) -> Conditioner {
+        let mode = if config.modes.contains(&ConditionerMode::Cool) {
+            ConditionerMode::Cool
+        } else {
+            config.modes.first().copied().unwrap_or(ConditionerMode::Auto)
+        };
+
         Conditioner {
+            id: config.id.clone(),
             enabled: false,
             controlled: false,
-            temperature: 20,
-            mode: ConditionerMode::Cool
+            temperature: 20.clamp(config.min_temperature, config.max_temperature),
+            mode
         }
     }
 
-    pub fn set(&self, conditioners: &[Conditioner]) -> Result<(), ServerError> {
+    pub fn inventory(&self) -> &[ConditionerConfig] {
+        &self.inventory
+    }
+
+    /// Validates all settings first, so invalid input does not change anything.
+    /// Every conditioner can be set only once in the request.
+    pub fn set(&self, settings: &[ConditionerSettings]) -> Result<(), ServerError> {
+        let mut indexes = Vec::with_capacity(settings.len());
+        for (i, s) in settings.iter().enumerate() {
+            let index = match &s.id {
+                Some(id) => self.inventory.iter().position(|c| c.id.eq_ignore_ascii_case(id)),
+                None => if i < self.inventory.len() { Some(i) } else { None }
+            };
+            let index = index.ok_or(LogicError::ConditionerNotFound)?;
+            if indexes.contains(&index) {
+                return Err(LogicError::DuplicateConditionerSettings.into());
+            }
+
+            Climate::check_settings(&self.inventory[index], s.temperature, Some(s.mode))?;
+            indexes.push(index);
+        }
+
         let mut guard = self.state.lock()?;
+        for (s, index) in settings.iter().zip(indexes) {
+            let to_set = &mut guard.conditioners[index];
+            to_set.enabled = s.enabled;
+            to_set.controlled = s.controlled;
+            to_set.temperature = s.temperature;
+            to_set.mode = s.mode;
+        }
+
+        let conditioners = self.effective(&guard.conditioners, &Local::now().naive_local())?;
+        drop(guard);
+
+        self.record_usage(&conditioners);
+        self.sync_remote(&conditioners)
+    }
+
+    pub fn schedule(&self) -> &ClimateSchedule {
+        &self.schedule
+    }
+
+    pub fn set_programs(&self, programs: Vec<ClimateProgram>) -> Result<(), ServerError> {
+        for program in &programs {
+            program.validate()?;
+
+            let config = self.inventory
+                .iter()
+                .find(|c| c.id.eq_ignore_ascii_case(&program.conditioner_id))
+                .ok_or(LogicError::ConditionerNotFound)?;
+
+            Climate::check_settings(config, program.temperature, program.mode)?;
+        }
+
+        self.schedule.set_programs(programs)?;
+        self.apply_schedule()
+    }
+
+    pub fn set_mode(&self, mode: ClimateMode) -> Result<(), ServerError> {
+        info!("climate mode: {:?}", mode);
+        self.schedule.set_mode(mode)?;
+        self.apply_schedule()
+    }
+
+    /// Sends current effective states to conditioners, should be called periodically
+    /// so program changes are applied in time.
+    pub fn apply_schedule(&self) -> Result<(), ServerError> {
+        let conditioners = self.effective_conditioners()?;
+        self.record_usage(&conditioners);
+        self.sync_remote(&conditioners)
+    }
+
+    pub fn start_schedule(climate: &Arc<Climate>, shutdown: &Shutdown) -> JoinHandle<()> {
+        let climate = climate.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.wait() => break
+                }
+
+                let climate = climate.clone();
+                match tokio::task::spawn_blocking(move || climate.apply_schedule()).await {
+                    Ok(Err(e)) => error!("error on climate schedule apply: {}", e),
+                    Err(e) => error!("climate schedule task failed: {}", e),
+                    Ok(Ok(())) => {}
+                }
+            }
+        })
+    }
+
+    /// Conditioner states after climate programs and away or vacation mode are applied.
+    pub fn effective_conditioners(&self) -> Result<Vec<Conditioner>, ServerError> {
+        let guard = self.state.lock()?;
+        self.effective(&guard.conditioners, &Local::now().naive_local())
+    }
+
+    fn effective(&self, conditioners: &[Conditioner], now: &NaiveDateTime) -> Result<Vec<Conditioner>, ServerError> {
+        let energy_saving = self.schedule.energy_saving()?;
+
+        let mut result = Vec::with_capacity(conditioners.len());
+        for (c, config) in conditioners.iter().zip(self.inventory.iter()) {
+            let mut c = c.clone();
 
-        for i in 0..conditioners.len() {
-            if let Some(conditioner) = conditioners.get(i) {
-                if let Some (to_set) = guard.conditioners.get_mut(i) {
-                    to_set.enabled = conditioner.enabled;
-                    to_set.controlled = conditioner.controlled;
-                    to_set.temperature = conditioner.temperature;
-                    to_set.mode = conditioner.mode;
+            if let Some(program) = self.schedule.active_program(&c.id, now)? {
+                c.temperature = program.temperature;
+                c.mode = program.mode.unwrap_or(c.mode);
+                c.enabled = program.enabled.unwrap_or(c.enabled);
+            }
+
+            if let Some(saving) = &energy_saving {
+                match c.mode {
+                    ConditionerMode::Cool => c.temperature = saving.cool_temperature,
+                    ConditionerMode::Heat => c.temperature = saving.heat_temperature,
+                    _ => {}
                 }
             }
+
+            c.temperature = c.temperature.clamp(config.min_temperature, config.max_temperature);
+            result.push(c);
+        }
+
+        Ok(result)
+    }
+
+    /// Usage is accounted on every state change, so runtime is not lost when the state is changed
+    /// by settings, programs or climate mode without a conditioners request.
+    fn record_usage(&self, conditioners: &[Conditioner]) {
+        if let Err(e) = self.usage.record_conditioners(conditioners) {
+            error!("error on conditioners usage update: {}", e);
+        }
+    }
+
+    /// Controlled conditioners of rooms with stale or missing readings are skipped, they keep the last sent state.
+    fn sync_remote(&self, conditioners: &[Conditioner]) -> Result<(), ServerError> {
+        let remote = match &self.remote {
+            Some(r) => r,
+            None => return Ok(())
+        };
+
+        let health = self.health()?;
+        let usable : Vec<Conditioner> = conditioners
+            .iter()
+            .zip(self.inventory.iter())
+            .filter(|(c, config)| !c.controlled || health.is_usable(config.room))
+            .map(|(c, _)| c.clone())
+            .collect();
+
+        remote.sync(&usable)
+    }
+
+    fn check_settings(config: &ConditionerConfig, temperature: i32, mode: Option<ConditionerMode>) -> Result<(), ServerError> {
+        if temperature < config.min_temperature || temperature > config.max_temperature {
+            return Err(LogicError::ConditionerTemperatureOutOfRange.into());
+        }
+
+        if let Some(mode) = mode {
+            if !config.modes.contains(&mode) {
+                return Err(LogicError::ConditionerModeUnsupported.into());
+            }
         }
 
         Ok(())
@@ -118,16 +518,207 @@ impl Climate {
 
     pub fn sensors(&self) -> Result<Sensors, ServerError> {
         let guard = self.state.lock()?;
-        Ok(guard.sensors.clone())
+        Ok(Climate::merged_sensors(&guard))
+    }
+
+    pub fn health(&self) -> Result<SensorsHealth, ServerError> {
+        let guard = self.state.lock()?;
+        Ok(Climate::merged_sensors(&guard).health(&self.staleness))
+    }
+
+    pub fn set_local(&self, id: &str, target: ClimateTarget, channel: Option<i32>, reading: ClimateReading) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        let local = LocalReading {
+            id: id.to_string(),
+            target,
+            channel,
+            reading,
+            time: time::unix_now()
+        };
+
+        match guard.local.iter_mut().find(|l| l.id == id) {
+            Some(l) => *l = local,
+            None => guard.local.push(local)
+        }
+
+        Ok(())
+    }
+
+    /// Stores reading decoded from 433 MHz weather sensor, readings are matched by channel.
+    pub fn set_radio(&self, reading: WeatherReading) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        let sensor = WeatherSensor {
+            channel: reading.channel,
+            temperature: reading.temperature,
+            humidity: reading.humidity,
+            low_battery: reading.low_battery,
+            time: Some(time::unix_now())
+        };
+
+        match guard.radio.iter_mut().find(|s| s.channel == sensor.channel) {
+            Some(s) => *s = sensor,
+            None => guard.radio.push(sensor)
+        }
+
+        Ok(())
+    }
+
+    fn merged_sensors(state: &State) -> Sensors {
+        let mut sensors = state.sensors.clone();
+        for radio in &state.radio {
+            sensors.apply_weather(*radio);
+        }
+        for local in &state.local {
+            sensors.apply(local);
+        }
+        sensors
     }
 
     pub fn calculate(&self, sensors: Sensors) -> Result<Vec<Conditioner>, ServerError> {
         let mut guard = self.state.lock()?;
         guard.sensors = sensors;
 
-        Ok(guard.conditioners
-            .iter()
-            .map(|c| c.clone())
-            .collect())
+        let health = Climate::merged_sensors(&guard).health(&self.staleness);
+        let mut conditioners = self.effective(&guard.conditioners, &Local::now().naive_local())?;
+        drop(guard);
+
+        for (c, config) in conditioners.iter_mut().zip(self.inventory.iter()) {
+            if c.controlled && !health.is_usable(config.room) {
+                warn!("conditioner {} control is blocked: room reading is stale or missing", &c.id);
+                c.controlled = false;
+            }
+        }
+
+        self.record_usage(&conditioners);
+
+        let controlled : Vec<Conditioner> = conditioners.iter().filter(|c| c.controlled).cloned().collect();
+        if let Err(e) = self.sync_remote(&controlled) {
+            error!("error on conditioners IR sync: {}", e);
+        }
+
+        Ok(conditioners)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use chrono::NaiveDate;
+    use crate::config::ClimateModesConfig;
+    use crate::utils::storage::Storage;
+
+    fn climate(name: &str) -> Climate {
+        let path = env::temp_dir().join(format!("rpi_home_climate_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let storage = Arc::new(Storage::new(path).unwrap());
+
+        let inventory = vec![ConditionerConfig {
+            id: "bedroom".to_owned(),
+            name: "Bedroom".to_owned(),
+            room: ClimateTarget::Bedroom,
+            modes: vec![ConditionerMode::Cool, ConditionerMode::Heat],
+            min_temperature: 16,
+            max_temperature: 30,
+            ir_protocol: None,
+            power_watts: None
+        }];
+        let schedule = Arc::new(ClimateSchedule::new(&ClimateModesConfig::default(), &storage).unwrap());
+        let usage = Arc::new(Usage::new(&inventory, &[], &storage).unwrap());
+
+        Climate::new(&StalenessConfig::default(), &inventory, &schedule, None, &usage)
+    }
+
+    fn program(start: &str, end: &str) -> ClimateProgram {
+        ClimateProgram {
+            conditioner_id: "bedroom".to_owned(),
+            days: Vec::new(),
+            start: start.to_owned(),
+            end: end.to_owned(),
+            temperature: 24,
+            mode: None,
+            enabled: Some(true)
+        }
+    }
+
+    fn settings(id: Option<&str>, temperature: i32, mode: ConditionerMode) -> ConditionerSettings {
+        ConditionerSettings {
+            id: id.map(|i| i.to_owned()),
+            enabled: true,
+            controlled: false,
+            temperature,
+            mode
+        }
+    }
+
+    fn assert_logic_error(result: Result<(), ServerError>, expected: LogicError) {
+        match result {
+            Err(ServerError::Logic(e)) => assert_eq!(e as i32, expected as i32),
+            other => panic!("unexpected result {:?}", other)
+        }
+    }
+
+    #[test]
+    fn sets_conditioner_by_id_or_index() {
+        let climate = climate("set");
+
+        climate.set(&[settings(Some("BEDROOM"), 22, ConditionerMode::Heat)]).unwrap();
+        let conditioners = climate.conditioners().unwrap();
+        assert!(conditioners[0].enabled);
+        assert_eq!(conditioners[0].temperature, 22);
+        assert_eq!(conditioners[0].mode, ConditionerMode::Heat);
+
+        climate.set(&[settings(None, 25, ConditionerMode::Cool)]).unwrap();
+        assert_eq!(climate.conditioners().unwrap()[0].temperature, 25);
+    }
+
+    #[test]
+    fn rejects_invalid_settings_without_changes() {
+        let climate = climate("invalid");
+
+        assert_logic_error(climate.set(&[settings(Some("kitchen"), 22, ConditionerMode::Cool)]), LogicError::ConditionerNotFound);
+        assert_logic_error(climate.set(&[settings(None, 22, ConditionerMode::Cool), settings(None, 22, ConditionerMode::Cool)]), LogicError::ConditionerNotFound);
+        assert_logic_error(climate.set(&[settings(Some("bedroom"), 22, ConditionerMode::Cool), settings(Some("Bedroom"), 23, ConditionerMode::Cool)]), LogicError::DuplicateConditionerSettings);
+        assert_logic_error(climate.set(&[settings(Some("bedroom"), 31, ConditionerMode::Cool)]), LogicError::ConditionerTemperatureOutOfRange);
+        assert_logic_error(climate.set(&[settings(Some("bedroom"), 15, ConditionerMode::Cool)]), LogicError::ConditionerTemperatureOutOfRange);
+        assert_logic_error(climate.set(&[settings(Some("bedroom"), 22, ConditionerMode::Dry)]), LogicError::ConditionerModeUnsupported);
+
+        let conditioners = climate.conditioners().unwrap();
+        assert!(!conditioners[0].enabled);
+        assert_eq!(conditioners[0].temperature, 20);
+    }
+
+    #[test]
+    fn applies_program_then_away_mode() {
+        let climate = climate("away");
+        climate.schedule.set_programs(vec![program("22:00", "06:00")]).unwrap();
+        let conditioners = climate.conditioners().unwrap();
+        let night = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(23, 0, 0).unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+
+        let effective = climate.effective(&conditioners, &night).unwrap();
+        assert!(effective[0].enabled);
+        assert_eq!(effective[0].temperature, 24);
+
+        let effective = climate.effective(&conditioners, &day).unwrap();
+        assert!(!effective[0].enabled);
+        assert_eq!(effective[0].temperature, 20);
+
+        climate.schedule.set_mode(ClimateMode::Away).unwrap();
+        let effective = climate.effective(&conditioners, &night).unwrap();
+        assert!(effective[0].enabled);
+        assert_eq!(effective[0].temperature, 27);
+    }
+
+    #[test]
+    fn reports_omitted_rooms_as_missing() {
+        let sensors = Sensors::new(Vec::new(), None, Some(0.0), None);
+        let health = sensors.health(&StalenessConfig::default());
+
+        assert!(health.is_usable(ClimateTarget::Bedroom));
+        assert!(!health.is_usable(ClimateTarget::Living));
+        assert!(health.readings.iter().any(|r| r.name == "living" && r.missing));
+        assert_eq!(sensors.room_temperatures(), vec![("bedroom", 0.0)]);
     }
 }
\ No newline at end of file
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::config::{ClimateSensorConfig, ClimateSensorKind};
+use crate::services::climate::Climate;
+use crate::services::shutdown::Shutdown;
+use crate::utils::bme280::Bme280;
+use crate::utils::climate_sensor::{ClimateReading, ClimateSensor, ClimateSensorError};
+use crate::utils::dht22::Dht22;
+use crate::utils::ds18b20::Ds18b20;
+
+pub type SensorReadings = Vec<(String, Result<ClimateReading, ClimateSensorError>)>;
+
+/// Periodically reads sensors connected to the raspberry pi and merges readings into `Climate`.
+pub struct ClimatePoller;
+
+struct PolledSensor {
+    config: ClimateSensorConfig,
+    sensor: Box<dyn ClimateSensor>
+}
+
+impl ClimatePoller {
+    pub fn start(configs: &[ClimateSensorConfig], w1_devices_path: &str, interval_seconds: u64, climate: &Arc<Climate>, shutdown: &Shutdown) -> Result<JoinHandle<()>, ClimateSensorError> {
+        let sensors = Arc::new(Self::sensors(configs, w1_devices_path)?);
+        let climate = climate.clone();
+        let shutdown = shutdown.clone();
+        let period = Duration::from_secs(interval_seconds.max(1));
+
+        Ok(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.wait() => break
+                }
+
+                let sensors = sensors.clone();
+                let climate = climate.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || Self::poll(&sensors, &climate)).await {
+                    error!("climate poll task failed: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Reads every sensor once, without climate state, used for bench testing.
+    pub fn read_once(configs: &[ClimateSensorConfig], w1_devices_path: &str) -> Result<SensorReadings, ClimateSensorError> {
+        Ok(Self::sensors(configs, w1_devices_path)?
+            .iter()
+            .map(|s| (s.config.id.clone(), s.sensor.read()))
+            .collect())
+    }
+
+    fn sensors(configs: &[ClimateSensorConfig], w1_devices_path: &str) -> Result<Vec<PolledSensor>, ClimateSensorError> {
+        let mut sensors = Vec::with_capacity(configs.len());
+        for config in configs {
+            let sensor : Box<dyn ClimateSensor> = match &config.kind {
+                ClimateSensorKind::Ds18b20 { device_id } => Box::new(Ds18b20::new(w1_devices_path, device_id)),
+                ClimateSensorKind::Dht22 { pin } => Box::new(Dht22::new(*pin)?),
+                ClimateSensorKind::Bme280 { address } => Box::new(Bme280::new(*address)?)
+            };
+
+            info!("climate sensor {}: {:?} -> {:?}", &config.id, &config.kind, &config.target);
+            sensors.push(PolledSensor {
+                config: config.clone(),
+                sensor
+            });
+        }
+
+        Ok(sensors)
+    }
+
+    fn poll(sensors: &[PolledSensor], climate: &Climate) {
+        for s in sensors {
+            let reading = match s.sensor.read() {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("error on climate sensor {} read: {}", &s.config.id, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = climate.set_local(&s.config.id, s.config.target, s.config.channel, reading) {
+                error!("error on climate sensor {} update: {}", &s.config.id, e);
+            }
+        }
+    }
+}
\ No newline at end of file
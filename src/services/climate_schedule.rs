@@ -0,0 +1,180 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ClimateModesConfig, EnergySavingConfig};
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::climate::ConditionerMode;
+use crate::utils::storage::Storage;
+
+const SCHEDULE_STATE : &str = "climate_schedule";
+const TIME_FORMAT : &str = "%H:%M";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClimateMode {
+    #[default]
+    Home,
+    Away,
+    Vacation
+}
+
+/// Setpoint of the conditioner for the time range, `days` are ISO weekdays (1 - monday),
+/// empty list means every day. Range where `end` is less than `start` passes midnight.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClimateProgram {
+    pub conditioner_id: String,
+    #[serde(default)]
+    pub days: Vec<u32>,
+    pub start: String,
+    pub end: String,
+    pub temperature: i32,
+    pub mode: Option<ConditionerMode>,
+    pub enabled: Option<bool>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScheduleState {
+    pub mode: ClimateMode,
+    pub programs: Vec<ClimateProgram>
+}
+
+pub struct ClimateSchedule {
+    modes: ClimateModesConfig,
+    storage: Arc<Storage>,
+    state: Mutex<ScheduleState>
+}
+
+impl ClimateProgram {
+    pub fn validate(&self) -> Result<(), ServerError> {
+        Self::parse_time(&self.start)?;
+        Self::parse_time(&self.end)?;
+        if self.days.iter().any(|d| !(1..=7).contains(d)) {
+            return Err(LogicError::InvalidClimateProgramTime.into());
+        }
+        Ok(())
+    }
+
+    fn parse_time(time: &str) -> Result<NaiveTime, ServerError> {
+        Ok(NaiveTime::parse_from_str(time, TIME_FORMAT)
+            .map_err(|_| LogicError::InvalidClimateProgramTime)?)
+    }
+
+    fn is_active(&self, now: &NaiveDateTime) -> bool {
+        if !self.days.is_empty() && !self.days.contains(&now.weekday().number_from_monday()) {
+            return false;
+        }
+
+        let (start, end) = match (Self::parse_time(&self.start), Self::parse_time(&self.end)) {
+            (Ok(s), Ok(e)) => (s, e),
+            _ => return false
+        };
+
+        let time = now.time();
+        if start <= end {
+            start <= time && time < end
+        } else {
+            start <= time || time < end
+        }
+    }
+}
+
+impl ClimateSchedule {
+    pub fn new(modes: &ClimateModesConfig, storage: &Arc<Storage>) -> Result<Self, ServerError> {
+        Ok(ClimateSchedule {
+            modes: modes.clone(),
+            storage: storage.clone(),
+            state: Mutex::new(storage.load(SCHEDULE_STATE)?)
+        })
+    }
+
+    pub fn state(&self) -> Result<ScheduleState, ServerError> {
+        let guard = self.state.lock()?;
+        Ok(guard.clone())
+    }
+
+    pub fn set_mode(&self, mode: ClimateMode) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        guard.mode = mode;
+        self.storage.save(SCHEDULE_STATE, &*guard)
+    }
+
+    /// Programs should be validated by the caller.
+    pub fn set_programs(&self, programs: Vec<ClimateProgram>) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        guard.programs = programs;
+        self.storage.save(SCHEDULE_STATE, &*guard)
+    }
+
+    /// Returns first active program of the conditioner.
+    pub fn active_program(&self, conditioner_id: &str, now: &NaiveDateTime) -> Result<Option<ClimateProgram>, ServerError> {
+        let guard = self.state.lock()?;
+        Ok(guard.programs
+            .iter()
+            .find(|p| p.conditioner_id.eq_ignore_ascii_case(conditioner_id) && p.is_active(now))
+            .cloned())
+    }
+
+    /// Returns energy saving setpoints if away or vacation mode is enabled.
+    pub fn energy_saving(&self) -> Result<Option<EnergySavingConfig>, ServerError> {
+        let guard = self.state.lock()?;
+        Ok(match guard.mode {
+            ClimateMode::Home => None,
+            ClimateMode::Away => Some(self.modes.away.clone()),
+            ClimateMode::Vacation => Some(self.modes.vacation.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn program(days: Vec<u32>, start: &str, end: &str) -> ClimateProgram {
+        ClimateProgram {
+            conditioner_id: "bedroom".to_owned(),
+            days,
+            start: start.to_owned(),
+            end: end.to_owned(),
+            temperature: 22,
+            mode: None,
+            enabled: None
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2024-01-01 is monday
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn validates_time_and_days() {
+        assert!(program(vec![1, 7], "08:00", "20:00").validate().is_ok());
+        assert!(program(Vec::new(), "8 am", "20:00").validate().is_err());
+        assert!(program(vec![0], "08:00", "20:00").validate().is_err());
+        assert!(program(vec![8], "08:00", "20:00").validate().is_err());
+    }
+
+    #[test]
+    fn passes_midnight() {
+        let night = program(Vec::new(), "22:00", "06:00");
+
+        assert!(night.is_active(&at(1, 23, 30)));
+        assert!(night.is_active(&at(2, 0, 0)));
+        assert!(night.is_active(&at(2, 5, 59)));
+        assert!(!night.is_active(&at(2, 6, 0)));
+        assert!(!night.is_active(&at(2, 21, 59)));
+    }
+
+    #[test]
+    fn filters_days() {
+        let weekend = program(vec![6, 7], "08:00", "20:00");
+
+        assert!(!weekend.is_active(&at(5, 12, 0)));
+        assert!(weekend.is_active(&at(6, 12, 0)));
+        assert!(weekend.is_active(&at(7, 12, 0)));
+        assert!(!weekend.is_active(&at(8, 12, 0)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::config::{ConditionerConfig, IrTransmitterConfig};
+use crate::server::server_error::ServerError;
+use crate::services::climate::Conditioner;
+use crate::services::safety::Actuator;
+use crate::utils::ir::{IrCommand, IrProtocol};
+use crate::utils::ir::transmitter::IrTransmitter;
+
+/// Command for one conditioner: id, protocol and state.
+type RemoteCommand = (String, IrProtocol, IrCommand);
+
+/// Sends conditioner states by IR, the frame is sent only when the state was changed.
+/// Transmitter is owned by a dedicated thread, because sending busy waits for the whole frame.
+pub struct ConditionerRemote {
+    protocols: HashMap<String, IrProtocol>,
+    commands: Sender<Vec<RemoteCommand>>,
+    stopped: Arc<AtomicBool>
+}
+
+impl ConditionerRemote {
+    pub fn new(config: &IrTransmitterConfig, conditioners: &[ConditionerConfig]) -> Result<Self, ServerError> {
+        let transmitter = IrTransmitter::new(config.pin, config.carrier_hz)?;
+        let (sender, receiver) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let thread_stopped = stopped.clone();
+        thread::Builder::new()
+            .name("ir".to_owned())
+            .spawn(move || ConditionerRemote::run(transmitter, receiver, &thread_stopped))?;
+
+        Ok(ConditionerRemote {
+            protocols: conditioners
+                .iter()
+                .filter_map(|c| c.ir_protocol.map(|p| (c.id.clone(), p)))
+                .collect(),
+            commands: sender,
+            stopped
+        })
+    }
+
+    /// Queues states of conditioners with IR protocol, returns without waiting for the transmission.
+    pub fn sync(&self, conditioners: &[Conditioner]) -> Result<(), ServerError> {
+        let commands : Vec<RemoteCommand> = conditioners
+            .iter()
+            .filter_map(|c| self.protocols.get(c.id()).map(|p| (c.id().to_string(), *p, c.ir_command())))
+            .collect();
+
+        if commands.is_empty() {
+            return Ok(());
+        }
+
+        self.commands.send(commands)
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "IR transmitter is stopped"))?;
+        Ok(())
+    }
+
+    fn run(mut transmitter: IrTransmitter, receiver: Receiver<Vec<RemoteCommand>>, stopped: &AtomicBool) {
+        let mut sent : HashMap<String, IrCommand> = HashMap::new();
+
+        for commands in receiver {
+            for (id, protocol, command) in commands {
+                if stopped.load(Ordering::SeqCst) {
+                    info!("IR transmitter is stopped, {:?} is not sent to conditioner {}", &command, &id);
+                    continue;
+                }
+
+                if sent.get(&id) == Some(&command) {
+                    continue;
+                }
+
+                info!("sending {:?} to conditioner {} by {:?}", &command, &id, protocol);
+                let pulses = protocol.encode(&command);
+                match transmitter.send(&pulses) {
+                    Ok(()) => {
+                        sent.insert(id, command);
+                    },
+                    Err(e) => error!("error on IR send to conditioner {}: {}", &id, e)
+                }
+            }
+        }
+
+        info!("IR transmitter is stopped");
+    }
+}
+
+/// Conditioners are not turned off, they keep the last sent state under their own thermostat,
+/// which is safe, while turning them off could leave the house unheated. Only queued frames are dropped,
+/// so a state calculated before the panic is not sent after it. The LED is on only during a frame.
+impl Actuator for ConditionerRemote {
+    fn safe_state(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    fn enforce_max_runtime(&self) {
+    }
+}
\ No newline at end of file
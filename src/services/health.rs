@@ -0,0 +1,264 @@
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use crate::services::camera_service::CameraService;
+use crate::server::server_error::ServerError;
+use crate::services::climate::Climate;
+use crate::services::shutdown::Shutdown;
+use crate::services::switches::Switches;
+use crate::services::water::Water;
+use crate::utils::storage::Storage;
+use crate::utils::systemd;
+
+const DEVICE_CONNECT_TIMEOUT : Duration = Duration::from_secs(2);
+const DEVICE_PROBE_INTERVAL : Duration = Duration::from_secs(60);
+
+/// Status of subsystems for uptime monitoring, checks do not move actuators or power sensors.
+pub struct Health {
+    camera: Arc<CameraService>,
+    storage: Arc<Storage>,
+    water: Arc<Water>,
+    climate: Arc<Climate>,
+    switches: Arc<Switches>,
+    shutdown: Shutdown,
+    started: AtomicBool,
+    /// Results of the last device probe, devices are probed in the background so requests do not make connections.
+    devices: Mutex<Vec<Check>>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Check {
+    name: String,
+    ok: bool,
+    /// Critical checks fail readiness and stop systemd watchdog notifications.
+    critical: bool,
+    detail: String
+}
+
+#[derive(Serialize, Debug)]
+pub struct Report {
+    ok: bool,
+    checks: Vec<Check>
+}
+
+/// Report without check details, which can contain paths, addresses and errors.
+#[derive(Serialize, Debug)]
+pub struct Summary {
+    ok: bool
+}
+
+impl Check {
+    fn new(name: &str, critical: bool, result: Result<String, String>) -> Self {
+        let (ok, detail) = match result {
+            Ok(d) => (true, d),
+            Err(d) => (false, d)
+        };
+
+        Check {
+            name: name.to_owned(),
+            ok,
+            critical,
+            detail
+        }
+    }
+}
+
+impl Report {
+    fn new(checks: Vec<Check>) -> Self {
+        Report {
+            ok: checks.iter().all(|c| c.ok),
+            checks
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.ok
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary {
+            ok: self.ok
+        }
+    }
+
+    fn failed(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|c| !c.ok)
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+}
+
+impl Health {
+    pub fn new(camera: &Arc<CameraService>, storage: &Arc<Storage>, water: &Arc<Water>, climate: &Arc<Climate>, switches: &Arc<Switches>, shutdown: &Shutdown) -> Self {
+        Health {
+            camera: camera.clone(),
+            storage: storage.clone(),
+            water: water.clone(),
+            climate: climate.clone(),
+            switches: switches.clone(),
+            shutdown: shutdown.clone(),
+            started: AtomicBool::new(false),
+            devices: Mutex::new(Vec::new())
+        }
+    }
+
+    /// Called when all subsystems are created and the server listens.
+    pub fn set_started(&self) {
+        self.started.store(true, Ordering::SeqCst);
+    }
+
+    /// All checks, devices are reported by the last background probe.
+    pub fn report(&self) -> Result<Report, ServerError> {
+        let mut checks = self.critical_checks();
+
+        checks.push(Check::new("camera", false, self.camera.check().map(|_| "ok".to_owned())));
+        checks.push(Check::new("climate_sensors", false, self.check_climate()));
+        checks.extend(self.devices.lock()?.iter().cloned());
+
+        Ok(Report::new(checks))
+    }
+
+    /// Server takes requests only after startup and until shutdown, and only with critical subsystems working.
+    pub fn readiness(&self) -> Report {
+        let mut checks = vec![
+            Check::new("started", true, match self.started.load(Ordering::SeqCst) {
+                true => Ok("ok".to_owned()),
+                false => Err("server is starting".to_owned())
+            }),
+            Check::new("shutdown", true, match self.shutdown.is_triggered() {
+                true => Err("server is shutting down".to_owned()),
+                false => Ok("ok".to_owned())
+            })
+        ];
+        checks.extend(self.critical_checks());
+
+        Report::new(checks)
+    }
+
+    /// Sends systemd watchdog notifications while critical checks pass, so systemd restarts the server
+    /// if GPIO or state storage is lost, as well as when the runtime hangs.
+    pub fn start(health: &Arc<Health>) -> Option<JoinHandle<()>> {
+        let systemd_interval = systemd::watchdog_interval()?;
+        let health = health.clone();
+
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(systemd_interval);
+            let mut failed = Vec::new();
+
+            loop {
+                interval.tick().await;
+
+                let task_health = health.clone();
+                let report = match tokio::task::spawn_blocking(move || Report::new(task_health.critical_checks())).await {
+                    Ok(r) => r,
+                    Err(e) => {
+                        error!("error on health check: {}", e);
+                        continue;
+                    }
+                };
+
+                let now_failed : Vec<String> = report.failed().iter().map(|n| n.to_string()).collect();
+                if now_failed != failed {
+                    let status = match now_failed.is_empty() {
+                        true => "STATUS=Healthy".to_owned(),
+                        false => format!("STATUS=Failed checks: {}", now_failed.join(", "))
+                    };
+                    if let Err(e) = systemd::notify(&status) {
+                        error!("error on systemd status notify: {}", e);
+                    }
+                    failed = now_failed;
+                }
+
+                if !report.is_ok() {
+                    warn!("critical health checks failed, systemd watchdog is not notified: {:?}", &failed);
+                    continue;
+                }
+
+                if let Err(e) = systemd::notify("WATCHDOG=1") {
+                    error!("error on systemd watchdog notify: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Connects to switch devices periodically, each connection blocks up to the connect timeout.
+    pub fn start_device_probes(health: &Arc<Health>, shutdown: &Shutdown) -> JoinHandle<()> {
+        let health = health.clone();
+        let shutdown = shutdown.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DEVICE_PROBE_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.wait() => break
+                }
+
+                let task_health = health.clone();
+                match tokio::task::spawn_blocking(move || task_health.probe_devices()).await {
+                    Ok(Err(e)) => error!("error on device probe: {}", e),
+                    Err(e) => error!("device probe task failed: {}", e),
+                    Ok(Ok(())) => {}
+                }
+            }
+        })
+    }
+
+    fn probe_devices(&self) -> Result<(), ServerError> {
+        let checks = match self.switches.devices() {
+            Ok(devices) => devices
+                .into_iter()
+                .map(|(name, ip, port)| Check::new(&format!("device:{}", name), false, check_device(&ip, port)))
+                .collect(),
+            Err(e) => vec![Check::new("devices", false, Err(e.to_string()))]
+        };
+
+        *self.devices.lock()? = checks;
+        Ok(())
+    }
+
+    fn critical_checks(&self) -> Vec<Check> {
+        vec![
+            Check::new("gpio", true, self.water.check_gpio()
+                .map(|pins| format!("{} pins initialized", pins))
+                .map_err(|e| e.to_string())),
+            Check::new("storage", true, self.storage.check_writable()
+                .map(|_| "writable".to_owned())
+                .map_err(|e| e.to_string()))
+        ]
+    }
+
+    fn check_climate(&self) -> Result<String, String> {
+        let health = self.climate.health().map_err(|e| e.to_string())?;
+
+        let stale = health.stale_readings();
+        if !stale.is_empty() {
+            return Err(format!("stale readings: {}", stale.join(", ")));
+        }
+
+        Ok(match health.last_update_age() {
+            Some(age) => format!("last update {}s ago", age),
+            None => "no readings yet".to_owned()
+        })
+    }
+}
+
+/// Device command servers are only connected to, no command is sent.
+fn check_device(ip: &str, port: u16) -> Result<String, String> {
+    let address = (ip, port)
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| "address not found".to_owned())?;
+
+    TcpStream::connect_timeout(&address, DEVICE_CONNECT_TIMEOUT)
+        .map(|_| "reachable".to_owned())
+        .map_err(|e| e.to_string())
+}
\ No newline at end of file
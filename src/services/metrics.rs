@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use hyper::StatusCode;
+
+use crate::server::server_error::ServerError;
+use crate::utils::prometheus::Exposition;
+
+/// Upper bounds of request latency histogram buckets in seconds.
+const LATENCY_BUCKETS : [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Counters of events which happen in request handlers, state of services is collected on scrape.
+pub struct Metrics {
+    state: Mutex<State>
+}
+
+#[derive(Default)]
+struct State {
+    requests: BTreeMap<(String, u16), u64>,
+    latencies: BTreeMap<String, Latency>,
+    logic_errors: BTreeMap<i32, u64>,
+    commands: BTreeMap<(String, bool), u64>
+}
+
+#[derive(Default)]
+struct Latency {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    seconds: f64
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            state: Mutex::new(State::default())
+        }
+    }
+
+    /// Path should be a registered handler path, so label values are bounded.
+    pub fn record_request(&self, path: &str, status: StatusCode, elapsed: Duration) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        *guard.requests.entry((path.to_owned(), status.as_u16())).or_default() += 1;
+
+        let seconds = elapsed.as_secs_f64();
+        let latency = guard.latencies.entry(path.to_owned()).or_default();
+        for (bucket, bound) in latency.buckets.iter_mut().zip(LATENCY_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        latency.count += 1;
+        latency.seconds += seconds;
+
+        Ok(())
+    }
+
+    pub fn record_logic_error(&self, code: i32) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        *guard.logic_errors.entry(code).or_default() += 1;
+        Ok(())
+    }
+
+    /// Counts commands sent to devices by the command client.
+    pub fn record_command(&self, device: &str, success: bool) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        *guard.commands.entry((device.to_owned(), success)).or_default() += 1;
+        Ok(())
+    }
+
+    pub fn write(&self, out: &mut Exposition) -> Result<(), ServerError> {
+        let guard = self.state.lock()?;
+
+        out.family("rpi_home_requests_total", "counter", "Handled requests by path and status.");
+        for ((path, status), count) in &guard.requests {
+            out.sample("rpi_home_requests_total", &[("path", path), ("status", &status.to_string())], *count as f64);
+        }
+
+        out.family("rpi_home_request_duration_seconds", "histogram", "Request handling time by path.");
+        for (path, latency) in &guard.latencies {
+            for (bucket, bound) in latency.buckets.iter().zip(LATENCY_BUCKETS.iter()) {
+                out.sample("rpi_home_request_duration_seconds_bucket", &[("path", path), ("le", &bound.to_string())], *bucket as f64);
+            }
+            out.sample("rpi_home_request_duration_seconds_bucket", &[("path", path), ("le", "+Inf")], latency.count as f64);
+            out.sample("rpi_home_request_duration_seconds_sum", &[("path", path)], latency.seconds);
+            out.sample("rpi_home_request_duration_seconds_count", &[("path", path)], latency.count as f64);
+        }
+
+        out.family("rpi_home_logic_errors_total", "counter", "Logic errors returned to clients by error code.");
+        for (code, count) in &guard.logic_errors {
+            out.sample("rpi_home_logic_errors_total", &[("code", &code.to_string())], *count as f64);
+        }
+
+        out.family("rpi_home_commands_total", "counter", "Commands sent to devices by result.");
+        for ((device, success), count) in &guard.commands {
+            let result = if *success { "success" } else { "failure" };
+            out.sample("rpi_home_commands_total", &[("device", device), ("result", result)], *count as f64);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_request_histogram() {
+        let metrics = Metrics::new();
+        metrics.record_request("water", StatusCode::OK, Duration::from_millis(20)).unwrap();
+        metrics.record_request("water", StatusCode::OK, Duration::from_secs(3)).unwrap();
+        metrics.record_request("water", StatusCode::BAD_REQUEST, Duration::from_secs(20)).unwrap();
+
+        let mut out = Exposition::new();
+        metrics.write(&mut out).unwrap();
+        let text = out.into_string();
+        let histogram : Vec<&str> = text.lines().filter(|l| l.starts_with("rpi_home_request_duration_seconds")).collect();
+
+        assert_eq!(histogram, vec![
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"0.005\"} 0",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"0.01\"} 0",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"0.025\"} 1",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"0.05\"} 1",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"0.1\"} 1",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"0.25\"} 1",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"0.5\"} 1",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"1\"} 1",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"2.5\"} 1",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"5\"} 2",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"10\"} 2",
+            "rpi_home_request_duration_seconds_bucket{path=\"water\",le=\"+Inf\"} 3",
+            "rpi_home_request_duration_seconds_sum{path=\"water\"} 23.02",
+            "rpi_home_request_duration_seconds_count{path=\"water\"} 3"
+        ]);
+        assert!(text.contains("rpi_home_requests_total{path=\"water\",status=\"200\"} 2\n"));
+        assert!(text.contains("rpi_home_requests_total{path=\"water\",status=\"400\"} 1\n"));
+    }
+
+    #[test]
+    fn counts_failed_commands() {
+        let metrics = Metrics::new();
+        metrics.record_command("lamp", true).unwrap();
+        metrics.record_command("lamp", false).unwrap();
+        metrics.record_command("lamp", false).unwrap();
+
+        let mut out = Exposition::new();
+        metrics.write(&mut out).unwrap();
+        let text = out.into_string();
+
+        assert!(text.contains("rpi_home_commands_total{device=\"lamp\",result=\"failure\"} 2\n"));
+        assert!(text.contains("rpi_home_commands_total{device=\"lamp\",result=\"success\"} 1\n"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use hyper::Body;
+use hyper::body::{Bytes, Sender};
+
+use crate::config::StreamConfig;
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::camera_service::{CameraService, Resolution};
+use crate::services::shutdown::Shutdown;
+use crate::utils::jpeg;
+
+pub const BOUNDARY : &str = "frame";
+
+/// Live `multipart/x-mixed-replace` stream, the camera is kept active while anyone is watching.
+pub struct MjpegStream {
+    config: StreamConfig,
+    camera: Arc<CameraService>,
+    shutdown: Shutdown,
+    viewers: Arc<AtomicUsize>
+}
+
+struct Viewer {
+    viewers: Arc<AtomicUsize>
+}
+
+impl MjpegStream {
+    pub fn new(config: &StreamConfig, camera: &Arc<CameraService>, shutdown: &Shutdown) -> Self {
+        MjpegStream {
+            config: config.clone(),
+            camera: camera.clone(),
+            shutdown: shutdown.clone(),
+            viewers: Arc::new(AtomicUsize::new(0))
+        }
+    }
+
+    /// Returns body which receives frames until the viewer disconnects or the server shuts down.
+    pub fn open(&self) -> Result<Body, ServerError> {
+        let max = self.config.max_viewers;
+        let added = self.viewers.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| if v < max { Some(v + 1) } else { None });
+        if added.is_err() {
+            return Err(LogicError::TooManyStreamViewers.into());
+        }
+
+        let viewer = Viewer {
+            viewers: self.viewers.clone()
+        };
+
+        let (sender, body) = Body::channel();
+        let config = self.config.clone();
+        let camera = self.camera.clone();
+        let shutdown = self.shutdown.clone();
+
+        tokio::spawn(async move {
+            let _viewer = viewer;
+            let _lease = camera.lease();
+
+            if let Err(e) = MjpegStream::send_frames(&config, &camera, &shutdown, sender).await {
+                error!("camera stream is stopped: {}", e);
+            }
+        });
+
+        Ok(body)
+    }
+
+    async fn send_frames(config: &StreamConfig, camera: &CameraService, shutdown: &Shutdown, mut sender: Sender) -> Result<(), ServerError> {
+        let mut interval = tokio::time::interval(Duration::from_secs(1) / config.fps.max(1));
+        let resize = config.width.is_some() || config.height.is_some();
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = shutdown.wait() => return Ok(())
+            }
+
+            // preview keeps the camera in the same mode as motion detection, stills switch it only for a while
+            let mut frame = camera.capture(Resolution::Preview).await?;
+            if resize {
+                let (width, height, quality) = (config.width, config.height, config.quality);
+                frame = tokio::task::spawn_blocking(move || jpeg::resize(&frame, width, height, quality)).await??;
+            }
+
+            let header = format!("--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n", BOUNDARY, frame.len());
+            frame.extend_from_slice(b"\r\n");
+
+            let sent = match sender.send_data(Bytes::from(header)).await {
+                Ok(()) => sender.send_data(Bytes::from(frame)).await,
+                Err(e) => Err(e)
+            };
+
+            if sent.is_err() {
+                info!("camera stream viewer disconnected");
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Drop for Viewer {
+    fn drop(&mut self) {
+        self.viewers.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::camera_service::tests::simulated_camera;
+
+    fn stream(camera: &Arc<CameraService>, max_viewers: usize) -> MjpegStream {
+        let config = StreamConfig {
+            fps: 50,
+            max_viewers,
+            ..StreamConfig::default()
+        };
+
+        MjpegStream::new(&config, camera, &Shutdown::new())
+    }
+
+    async fn wait_released(stream: &MjpegStream, camera: &CameraService) {
+        for _ in 0..100 {
+            if stream.viewers.load(Ordering::SeqCst) == 0 && camera.leases() == 0 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        panic!("stream viewer was not released");
+    }
+
+    #[tokio::test]
+    async fn sends_jpeg_parts() {
+        let camera = simulated_camera();
+        let stream = stream(&camera, 1);
+        let mut body = stream.open().unwrap();
+
+        let header = hyper::body::HttpBody::data(&mut body).await.unwrap().unwrap();
+        let frame = hyper::body::HttpBody::data(&mut body).await.unwrap().unwrap();
+
+        assert!(String::from_utf8_lossy(&header).starts_with("--frame\r\nContent-Type: image/jpeg\r\n"));
+        assert!(frame.ends_with(b"\r\n"));
+        assert!(image::load_from_memory_with_format(&frame[..frame.len() - 2], image::ImageFormat::Jpeg).is_ok());
+    }
+
+    #[tokio::test]
+    async fn limits_viewers() {
+        let camera = simulated_camera();
+        let stream = stream(&camera, 2);
+
+        let _first = stream.open().unwrap();
+        let _second = stream.open().unwrap();
+
+        assert!(matches!(stream.open(), Err(ServerError::Logic(LogicError::TooManyStreamViewers))));
+    }
+
+    #[tokio::test]
+    async fn releases_viewer_and_lease_when_body_is_dropped() {
+        let camera = simulated_camera();
+        let stream = stream(&camera, 1);
+
+        let body = stream.open().unwrap();
+        assert!(stream.open().is_err());
+
+        drop(body);
+        wait_released(&stream, &camera).await;
+
+        assert!(stream.open().is_ok());
+    }
+}
\ No newline at end of file
@@ -1,3 +1,22 @@
 pub mod climate;
 pub mod computers;
-pub mod switches;
\ No newline at end of file
+pub mod switches;
+pub mod water;
+pub mod plants;
+pub mod soil;
+pub mod watering_scheduler;
+pub mod climate_poller;
+pub mod conditioner_remote;
+pub mod climate_schedule;
+pub mod usage;
+pub mod timelapse;
+pub mod photo_cache;
+pub mod camera_service;
+pub mod mjpeg_stream;
+pub mod motion;
+pub mod servos;
+pub mod camera_rig;
+pub mod safety;
+pub mod shutdown;
+pub mod metrics;
+pub mod health;
\ No newline at end of file
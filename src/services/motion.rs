@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use image::GrayImage;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::config::MotionConfig;
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::camera_service::{CameraService, Resolution};
+use crate::services::shutdown::Shutdown;
+use crate::utils::image_metrics::{self, Region};
+use crate::utils::jpeg;
+use crate::utils::storage::Storage;
+use crate::utils::time::unix_now;
+
+const EVENTS_STATE : &str = "motion_events";
+const THUMBNAIL_WIDTH : u32 = 160;
+const THUMBNAIL_QUALITY : u8 = 70;
+const EVENTS_CAPACITY : usize = 16;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MotionEvent {
+    pub time: u64,
+    pub change: f32
+}
+
+/// Compares consecutive frames and keeps snapshots of frames with motion.
+pub struct MotionDetector {
+    config: MotionConfig,
+    path: PathBuf,
+    storage: Arc<Storage>,
+    events: Mutex<VecDeque<MotionEvent>>,
+    sender: broadcast::Sender<MotionEvent>
+}
+
+impl MotionDetector {
+    pub fn new(config: &MotionConfig, storage: &Arc<Storage>) -> Result<Self, ServerError> {
+        fs::create_dir_all(&config.path)?;
+        let (sender, _) = broadcast::channel(EVENTS_CAPACITY);
+
+        Ok(MotionDetector {
+            config: config.clone(),
+            path: PathBuf::from(&config.path),
+            storage: storage.clone(),
+            events: Mutex::new(storage.load(EVENTS_STATE)?),
+            sender
+        })
+    }
+
+    /// Holds a camera lease until shutdown, so the camera stays active and powered for the whole time
+    /// motion detection is configured. Frames and event snapshots are captured at preview resolution to keep the load low.
+    pub fn start(detector: &Arc<MotionDetector>, camera: &Arc<CameraService>, shutdown: &Shutdown) -> JoinHandle<()> {
+        let detector = detector.clone();
+        let camera = camera.clone();
+        let shutdown = shutdown.clone();
+
+        tokio::spawn(async move {
+            // frames are captured often, so the camera should not be deactivated between them
+            let _lease = camera.lease();
+            let mut interval = tokio::time::interval(Duration::from_millis(detector.config.interval_millis.max(1)));
+            let mut previous : Option<GrayImage> = None;
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.wait() => break
+                }
+
+                let photo = match camera.capture(Resolution::Preview).await {
+                    Ok(p) => p,
+                    Err(e) => {
+                        error!("error on motion detection capture: {}", e);
+                        continue;
+                    }
+                };
+
+                let task_detector = detector.clone();
+                let result = tokio::task::spawn_blocking(move || task_detector.process(&photo, previous.as_ref())).await;
+                previous = match result {
+                    Ok(Ok(fingerprint)) => Some(fingerprint),
+                    Ok(Err(e)) => {
+                        error!("error on motion detection: {}", e);
+                        None
+                    },
+                    Err(e) => {
+                        error!("motion detection task failed: {}", e);
+                        None
+                    }
+                };
+            }
+        })
+    }
+
+    /// Other subsystems receive events as soon as they are detected.
+    pub fn subscribe(&self) -> broadcast::Receiver<MotionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Returns events detected after `since`, oldest first.
+    pub fn events(&self, since: Option<u64>) -> Result<Vec<MotionEvent>, ServerError> {
+        let guard = self.events.lock()?;
+        Ok(guard
+            .iter()
+            .filter(|e| since.map(|s| e.time > s).unwrap_or(true))
+            .cloned()
+            .collect())
+    }
+
+    pub fn thumbnail(&self, time: u64) -> Result<Vec<u8>, io::Error> {
+        fs::read(self.thumbnail_path(time))
+    }
+
+    /// Returns the preview frame saved when the event was detected.
+    pub fn snapshot(&self, time: u64) -> Result<Vec<u8>, ServerError> {
+        match fs::read(self.snapshot_path(time)) {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(LogicError::MotionSnapshotNotFound.into()),
+            Err(e) => Err(e.into())
+        }
+    }
+
+    fn process(&self, photo: &[u8], previous: Option<&GrayImage>) -> Result<GrayImage, ServerError> {
+        let analysis = image_metrics::analyze(photo, &Region::default(), previous)?;
+
+        if let Some(change) = analysis.metrics.change {
+            if change >= self.config.threshold {
+                self.save_event(photo, change)?;
+            }
+        }
+
+        Ok(analysis.fingerprint)
+    }
+
+    fn save_event(&self, photo: &[u8], change: f32) -> Result<(), ServerError> {
+        let time = unix_now();
+        let mut guard = self.events.lock()?;
+
+        if let Some(last) = guard.back() {
+            if time < last.time + self.config.cooldown_seconds {
+                return Ok(());
+            }
+        }
+
+        fs::write(self.snapshot_path(time), photo)?;
+        fs::write(self.thumbnail_path(time), jpeg::resize(photo, Some(THUMBNAIL_WIDTH), None, THUMBNAIL_QUALITY)?)?;
+
+        let event = MotionEvent {
+            time,
+            change
+        };
+
+        guard.push_back(event.clone());
+        while guard.len() > self.config.max_events {
+            if let Some(old) = guard.pop_front() {
+                self.remove_files(old.time)?;
+            }
+        }
+
+        self.storage.save(EVENTS_STATE, &*guard)?;
+
+        // nobody could be subscribed, it is not an error
+        let _ = self.sender.send(event);
+        Ok(())
+    }
+
+    fn remove_files(&self, time: u64) -> Result<(), io::Error> {
+        for path in [self.snapshot_path(time), self.thumbnail_path(time)] {
+            if let Err(e) = fs::remove_file(path) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn snapshot_path(&self, time: u64) -> PathBuf {
+        self.path.join(format!("{}.jpg", time))
+    }
+
+    fn thumbnail_path(&self, time: u64) -> PathBuf {
+        self.path.join(format!("{}-thumb.jpg", time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn detector(name: &str) -> MotionDetector {
+        let path = env::temp_dir().join(format!("rpi_home_motion_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let config = MotionConfig {
+            path: path.join("snapshots").to_string_lossy().into_owned(),
+            interval_millis: 500,
+            threshold: 0.1,
+            cooldown_seconds: 0,
+            max_events: 4
+        };
+
+        MotionDetector::new(&config, &Arc::new(Storage::new(path).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn keeps_snapshots_of_stored_events() {
+        let detector = detector("snapshots");
+        let photo = jpeg::simulated_frame(0, 64, 48).unwrap();
+
+        detector.save_event(&photo, 0.5).unwrap();
+        let time = detector.events(None).unwrap()[0].time;
+        assert_eq!(detector.snapshot(time).unwrap(), photo);
+        assert!(detector.thumbnail(time).is_ok());
+        assert!(matches!(detector.snapshot(time + 1), Err(ServerError::Logic(LogicError::MotionSnapshotNotFound))));
+    }
+}
\ No newline at end of file
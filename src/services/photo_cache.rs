@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::Mutex;
+
+use crate::config::CameraConfig;
+use crate::server::server_error::ServerError;
+use crate::services::camera_service::{CameraService, Resolution};
+
+pub struct Photo {
+    pub data: Vec<u8>,
+    pub time: SystemTime,
+    /// Unique for every captured photo, used as base for `ETag`.
+    pub tag: String,
+    captured: Instant
+}
+
+/// Keeps the last photo for a short time, so concurrent requests share one capture.
+pub struct PhotoCache {
+    camera: Arc<CameraService>,
+    max_age: Duration,
+    last: Mutex<Option<Arc<Photo>>>
+}
+
+impl PhotoCache {
+    pub fn new(config: &CameraConfig, camera: &Arc<CameraService>) -> Self {
+        PhotoCache {
+            camera: camera.clone(),
+            max_age: Duration::from_secs(config.cache_seconds),
+            last: Mutex::new(None)
+        }
+    }
+
+    pub async fn photo(&self) -> Result<Arc<Photo>, ServerError> {
+        // lock is held during capture, so waiting requests get the same photo
+        let mut guard = self.last.lock().await;
+        if let Some(photo) = &*guard {
+            if photo.captured.elapsed() < self.max_age {
+                return Ok(photo.clone());
+            }
+        }
+
+        let data = self.camera.capture(Resolution::Full).await?;
+
+        let time = SystemTime::now();
+        let nanos = time.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let photo = Arc::new(Photo {
+            tag: format!("{:x}-{:x}", nanos, data.len()),
+            data,
+            time,
+            captured: Instant::now()
+        });
+
+        *guard = Some(photo.clone());
+        Ok(photo)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::PlantConfig;
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::soil::Soil;
+use crate::services::water::Water;
+use crate::utils::storage::Storage;
+use crate::utils::time;
+
+const HISTORY_STATE : &str = "plant_history";
+
+pub struct Plants {
+    plants: Vec<PlantConfig>,
+    water: Arc<Water>,
+    soil: Arc<Soil>,
+    storage: Arc<Storage>,
+    state: Mutex<State>
+}
+
+struct State {
+    history: HashMap<String, History>
+}
+
+/// Kept in the state storage, so watering limits hold across restarts.
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct History {
+    last_watering: Option<u64>,
+    total_ml: f32,
+    day: u64,
+    day_ml: f32
+}
+
+/// Watering is registered before the pump is started and reverted if the pump fails.
+struct Registration {
+    time: u64,
+    previous_watering: Option<u64>
+}
+
+#[derive(Serialize, Debug)]
+pub struct PlantStatus {
+    id: String,
+    name: String,
+    pump_id: String,
+    last_watering: Option<u64>,
+    total_ml: f32,
+    today_ml: f32
+}
+
+#[derive(Debug)]
+pub enum WateringOutcome {
+    Watered(f32),
+    NotEnoughWater,
+    TooFrequent(u64),
+    DailyVolumeExceeded(f32),
+    MoistEnough(f32)
+}
+
+impl Plants {
+    pub fn new(plants: &[PlantConfig], water: &Arc<Water>, soil: &Arc<Soil>, storage: &Arc<Storage>) -> Result<Self, ServerError> {
+        for plant in plants {
+            water.check_pump(&plant.pump_id)?;
+            if let Some(sensor_id) = &plant.water_sensor_id {
+                water.check_sensor(sensor_id)?;
+            }
+            if let Some(sensor_id) = &plant.soil_sensor_id {
+                soil.check_sensor(sensor_id)?;
+            }
+        }
+
+        Ok(Plants {
+            plants: plants.to_vec(),
+            water: water.clone(),
+            soil: soil.clone(),
+            storage: storage.clone(),
+            state: Mutex::new(State {
+                history: storage.load(HISTORY_STATE)?
+            })
+        })
+    }
+
+    pub fn statuses(&self) -> Result<Vec<PlantStatus>, ServerError> {
+        let guard = self.state.lock()?;
+        let today = time::unix_day(time::unix_now());
+
+        Ok(self.plants
+            .iter()
+            .map(|p| {
+                let history = guard.history.get(&p.id).cloned().unwrap_or_default();
+                PlantStatus {
+                    id: p.id.clone(),
+                    name: p.name.clone(),
+                    pump_id: p.pump_id.clone(),
+                    last_watering: history.last_watering,
+                    total_ml: history.total_ml,
+                    today_ml: if history.day == today { history.day_ml } else { 0.0 }
+                }
+            })
+            .collect())
+    }
+
+    /// Waters the plant, watering limits are enforced even if `force` is set,
+    /// `force` only skips the water level check. Blocks the thread while the pump runs.
+    pub fn water(&self, plant_id: &str, volume_ml: Option<f32>, force: bool) -> Result<WateringOutcome, ServerError> {
+        let plant = self.find(plant_id)?;
+        let pump_id = Some(plant.pump_id.as_str());
+
+        let is_enough_water = match &plant.water_sensor_id {
+            Some(sensor_id) => self.water.is_enough(Some(sensor_id))?,
+            None => self.water.is_enough_for_pump(pump_id)?
+        };
+
+        if !force && !is_enough_water {
+            return Ok(WateringOutcome::NotEnoughWater);
+        }
+
+        let run = self.water.duration_for_volume(pump_id, volume_ml.unwrap_or(plant.amount_ml))?;
+        let volume_ml = run.volume_ml;
+
+        let registration = match self.register_watering(plant, volume_ml)? {
+            Ok(r) => r,
+            Err(outcome) => return Ok(outcome)
+        };
+
+        if let Err(e) = self.water.enable_pump(pump_id, run.duration) {
+            if let Err(e) = self.revert_watering(plant, volume_ml, &registration) {
+                error!("error on plant {} watering revert: {}", &plant.id, e);
+            }
+            return Err(e);
+        }
+
+        Ok(WateringOutcome::Watered(volume_ml))
+    }
+
+    /// Plants watered by the pump, running the pump directly would skip their watering limits.
+    pub fn plants_of_pump(&self, pump_id: Option<&str>) -> Result<Vec<&str>, ServerError> {
+        let pump_id = self.water.pump_id(pump_id)?;
+        Ok(self.plants
+            .iter()
+            .filter(|p| p.pump_id.eq_ignore_ascii_case(pump_id))
+            .map(|p| p.id.as_str())
+            .collect())
+    }
+
+    /// Waters plants with enabled auto watering, if the plant has soil sensor and threshold
+    /// it is watered only when moisture is below the threshold.
+    pub fn auto_water(&self) -> Result<(), ServerError> {
+        for plant in self.plants.iter().filter(|p| p.auto_watering) {
+            let outcome = match Plants::check_moisture(&self.soil, plant) {
+                Ok(Some(outcome)) => Ok(outcome),
+                Ok(None) => self.water(&plant.id, None, false),
+                Err(e) => Err(e)
+            };
+
+            match outcome {
+                Ok(WateringOutcome::Watered(volume)) => info!("plant {} was watered automatically with {}ml", &plant.id, volume),
+                Ok(WateringOutcome::NotEnoughWater) => warn!("plant {} was not watered: not enough water", &plant.id),
+                Ok(_) => debug!("plant {} does not need watering", &plant.id),
+                Err(e) => error!("error on plant {} auto watering: {}", &plant.id, e)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_moisture(soil: &Soil, plant: &PlantConfig) -> Result<Option<WateringOutcome>, ServerError> {
+        if let (Some(sensor_id), Some(threshold)) = (&plant.soil_sensor_id, plant.moisture_threshold) {
+            let moisture = soil.moisture(sensor_id)?.moisture();
+            if moisture >= threshold {
+                return Ok(Some(WateringOutcome::MoistEnough(moisture)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn find(&self, id: &str) -> Result<&PlantConfig, ServerError> {
+        let plant = self.plants
+            .iter()
+            .find(|p| p.id.eq_ignore_ascii_case(id));
+
+        Ok(plant.ok_or(LogicError::PlantNotFound)?)
+    }
+
+    /// Checks watering limits of the plant and, if they allow it, registers and saves the watering.
+    fn register_watering(&self, plant: &PlantConfig, volume_ml: f32) -> Result<Result<Registration, WateringOutcome>, ServerError> {
+        let mut guard = self.state.lock()?;
+        let mut history = guard.history.get(&plant.id).cloned().unwrap_or_default();
+
+        let registration = match history.register(plant, volume_ml, time::unix_now()) {
+            Ok(r) => r,
+            Err(outcome) => return Ok(Err(outcome))
+        };
+
+        let previous = guard.history.insert(plant.id.clone(), history);
+        if let Err(e) = self.storage.save(HISTORY_STATE, &guard.history) {
+            match previous {
+                Some(p) => guard.history.insert(plant.id.clone(), p),
+                None => guard.history.remove(&plant.id)
+            };
+            return Err(e);
+        }
+
+        Ok(Ok(registration))
+    }
+
+    fn revert_watering(&self, plant: &PlantConfig, volume_ml: f32, registration: &Registration) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+        if let Some(history) = guard.history.get_mut(&plant.id) {
+            history.revert(volume_ml, registration);
+        }
+
+        self.storage.save(HISTORY_STATE, &guard.history)
+    }
+}
+
+impl History {
+    fn register(&mut self, plant: &PlantConfig, volume_ml: f32, now: u64) -> Result<Registration, WateringOutcome> {
+        let today = time::unix_day(now);
+
+        if let Some(last) = self.last_watering {
+            let next = last + plant.min_interval_seconds;
+            if now < next {
+                return Err(WateringOutcome::TooFrequent(next - now));
+            }
+        }
+
+        if self.day != today {
+            self.day = today;
+            self.day_ml = 0.0;
+        }
+
+        if self.day_ml + volume_ml > plant.max_daily_ml {
+            return Err(WateringOutcome::DailyVolumeExceeded(plant.max_daily_ml - self.day_ml));
+        }
+
+        let registration = Registration {
+            time: now,
+            previous_watering: self.last_watering
+        };
+
+        self.last_watering = Some(now);
+        self.total_ml += volume_ml;
+        self.day_ml += volume_ml;
+
+        Ok(registration)
+    }
+
+    /// Watering registered after the reverted one keeps its time.
+    fn revert(&mut self, volume_ml: f32, registration: &Registration) {
+        self.total_ml -= volume_ml;
+        if self.day == time::unix_day(registration.time) {
+            self.day_ml -= volume_ml;
+        }
+        if self.last_watering == Some(registration.time) {
+            self.last_watering = registration.previous_watering;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::soil::tests::simulated_soil;
+
+    fn plant(soil_sensor_id: Option<&str>, moisture_threshold: Option<f32>) -> PlantConfig {
+        PlantConfig {
+            id: "basil".to_owned(),
+            name: "Basil".to_owned(),
+            pump_id: "default".to_owned(),
+            water_sensor_id: None,
+            amount_ml: 100.0,
+            min_interval_seconds: 3600,
+            max_daily_ml: 300.0,
+            auto_watering: true,
+            soil_sensor_id: soil_sensor_id.map(|s| s.to_owned()),
+            moisture_threshold
+        }
+    }
+
+    #[test]
+    fn skips_moist_soil() {
+        // 75% moisture
+        let soil = simulated_soil(500);
+        let outcome = Plants::check_moisture(&soil, &plant(Some("pot"), Some(60.0))).unwrap();
+
+        assert!(matches!(outcome, Some(WateringOutcome::MoistEnough(m)) if m == 75.0));
+    }
+
+    #[test]
+    fn waters_dry_soil() {
+        // 25% moisture
+        let soil = simulated_soil(700);
+        assert!(Plants::check_moisture(&soil, &plant(Some("pot"), Some(60.0))).unwrap().is_none());
+    }
+
+    #[test]
+    fn waters_without_threshold_or_sensor() {
+        let soil = simulated_soil(500);
+
+        assert!(Plants::check_moisture(&soil, &plant(Some("pot"), None)).unwrap().is_none());
+        assert!(Plants::check_moisture(&soil, &plant(None, Some(60.0))).unwrap().is_none());
+    }
+
+    #[test]
+    fn reverts_watering_when_pump_fails() {
+        let plant = plant(None, None);
+        let mut history = History::default();
+        let first = 86_400 * 100;
+
+        history.register(&plant, 100.0, first).unwrap();
+        let registration = history.register(&plant, 100.0, first + 3600).unwrap();
+        history.revert(100.0, &registration);
+
+        assert_eq!(history.last_watering, Some(first));
+        assert_eq!((history.total_ml, history.day_ml), (100.0, 100.0));
+        // limits count only the watering which was done
+        assert!(history.register(&plant, 200.0, first + 3600).is_ok());
+    }
+
+    #[test]
+    fn checks_watering_limits() {
+        let plant = plant(None, None);
+        let mut history = History::default();
+        let first = 86_400 * 100;
+
+        history.register(&plant, 100.0, first).unwrap();
+
+        assert!(matches!(history.register(&plant, 100.0, first + 600), Err(WateringOutcome::TooFrequent(3000))));
+        assert!(matches!(history.register(&plant, 250.0, first + 3600), Err(WateringOutcome::DailyVolumeExceeded(v)) if v == 200.0));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,164 @@
+use std::io;
+use std::panic;
+use std::process;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+
+use crate::config::WatchdogConfig;
+use crate::services::shutdown::Shutdown;
+use crate::utils::watchdog::Watchdog;
+
+const CHECK_INTERVAL : Duration = Duration::from_secs(1);
+
+/// Hardware which can harm plants or the house if it is left running.
+pub trait Actuator: Send + Sync {
+    /// Turns everything off, can be called from the panic hook with poisoned locks.
+    fn safe_state(&self);
+
+    /// Turns off everything which runs longer than allowed.
+    fn enforce_max_runtime(&self);
+}
+
+/// Drives actuators to the safe state on panic or termination signal,
+/// limits their runtime and feeds the hardware watchdog, systemd watchdog is fed by health checks.
+pub struct Safety {
+    actuators: Vec<Arc<dyn Actuator>>,
+    watchdog_config: Option<WatchdogConfig>,
+    watchdog_interval: Duration,
+    watchdog: Mutex<Option<Watchdog>>
+}
+
+impl Safety {
+    pub fn new(config: &Option<WatchdogConfig>, actuators: Vec<Arc<dyn Actuator>>) -> Self {
+        Safety {
+            actuators,
+            watchdog_config: config.clone(),
+            watchdog_interval: config.as_ref().map(|c| Duration::from_secs(c.interval_seconds.max(1))).unwrap_or(Duration::ZERO),
+            watchdog: Mutex::new(None)
+        }
+    }
+
+    /// Opens the hardware watchdog, which arms it. Called when startup is finished,
+    /// so a failing startup is restarted by systemd instead of rebooting the Pi in a loop.
+    pub fn arm(&self) -> Result<(), io::Error> {
+        let config = match &self.watchdog_config {
+            Some(c) => c,
+            None => return Ok(())
+        };
+
+        info!("hardware watchdog: {}", &config.path);
+        let mut watchdog = Watchdog::open(&config.path)?;
+        watchdog.keep_alive()?;
+
+        *self.watchdog.lock().unwrap_or_else(PoisonError::into_inner) = Some(watchdog);
+        Ok(())
+    }
+
+    pub fn safe_state(&self) {
+        for actuator in &self.actuators {
+            actuator.safe_state();
+        }
+    }
+
+    /// Default hook is called after actuators are stopped, so the panic is still logged to stderr.
+    pub fn install_panic_hook(safety: &Arc<Safety>) {
+        let safety = safety.clone();
+        let default_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            error!("panic, driving actuators to safe state: {}", info);
+            safety.safe_state();
+            default_hook(info);
+        }));
+    }
+
+    /// Watchdog is fed from the async runtime, so the Pi reboots if the runtime hangs.
+    pub fn start(safety: &Arc<Safety>) -> JoinHandle<()> {
+        let safety = safety.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            let mut fed : Option<Instant> = None;
+
+            loop {
+                interval.tick().await;
+
+                for actuator in &safety.actuators {
+                    actuator.enforce_max_runtime();
+                }
+
+                if fed.map(|f| f.elapsed() >= safety.watchdog_interval).unwrap_or(true) {
+                    safety.feed_watchdog();
+                    fed = Some(Instant::now());
+                }
+            }
+        })
+    }
+
+    /// Triggers shutdown on SIGINT or SIGTERM, the second signal stops actuators and exits without draining.
+    pub fn listen_signals(safety: &Arc<Safety>, shutdown: &Shutdown) -> JoinHandle<()> {
+        let safety = safety.clone();
+        let shutdown = shutdown.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = wait_signal().await {
+                    error!("error on signal listening: {}", e);
+                    return;
+                }
+
+                if shutdown.is_triggered() {
+                    warn!("termination signal received again, exiting without draining");
+                    safety.stop();
+                    process::exit(1);
+                }
+
+                info!("termination signal received, shutting down");
+                shutdown.trigger();
+            }
+        })
+    }
+
+    /// Disarms the watchdog, so a normal exit does not reboot the Pi.
+    pub fn stop(&self) {
+        self.safe_state();
+
+        let watchdog = self.watchdog.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if let Some(watchdog) = watchdog {
+            if let Err(e) = watchdog.disarm() {
+                error!("error on watchdog disarm: {}", e);
+            }
+        }
+    }
+
+    fn feed_watchdog(&self) {
+        let mut guard = self.watchdog.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(watchdog) = guard.as_mut() {
+            if let Err(e) = watchdog.keep_alive() {
+                error!("error on watchdog feed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn wait_signal() -> Result<(), io::Error> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+
+    tokio::select! {
+        _ = terminate.recv() => {},
+        _ = interrupt.recv() => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+async fn wait_signal() -> Result<(), io::Error> {
+    tokio::signal::ctrl_c().await
+}
\ No newline at end of file
@@ -0,0 +1,314 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::sync::{Arc, Mutex, TryLockError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ServoConfig;
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::safety::Actuator;
+use crate::utils::servo::{DEGREE_END, DEGREE_START, Servo, ServoCalibration};
+use crate::utils::storage::Storage;
+
+const SERVOS_STATE : &str = "servos";
+/// State of the only servo before multiple servos were supported, it is moved to the default servo.
+const LEGACY_SERVO_STATE : &str = "servo";
+const LEGACY_SERVO_ID : &str = "default";
+const STEP_INTERVAL : Duration = Duration::from_millis(20);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ServoState {
+    pub position: f32,
+    pub presets: BTreeMap<String, f32>,
+    pub calibration: Option<ServoCalibration>
+}
+
+#[derive(Serialize, Debug)]
+pub struct ServoStatus {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub state: ServoState
+}
+
+struct Entry {
+    id: String,
+    name: String,
+    speed: f32,
+    servo: Mutex<Servo>
+}
+
+/// Servos which remember their positions and named presets between restarts.
+pub struct ServoControl {
+    servos: Vec<Entry>,
+    storage: Arc<Storage>,
+    states: Mutex<HashMap<String, ServoState>>,
+    stopped: AtomicBool
+}
+
+impl ServoControl {
+    pub fn new(configs: &[ServoConfig], storage: &Arc<Storage>) -> Result<Self, ServerError> {
+        let mut states = ServoControl::load_states(storage)?;
+        let mut servos = Vec::with_capacity(configs.len());
+
+        for config in configs {
+            let state = states.entry(config.id.clone()).or_default();
+            let calibration = state.calibration.unwrap_or(config.calibration);
+
+            info!("servo {} ({}): {:?}", &config.id, &config.name, config.pin);
+            servos.push(Entry {
+                id: config.id.clone(),
+                name: config.name.clone(),
+                speed: config.speed_degrees_per_second,
+                servo: Mutex::new(Servo::new(config.pin, calibration, state.position)?)
+            });
+        }
+
+        Ok(ServoControl {
+            servos,
+            storage: storage.clone(),
+            states: Mutex::new(states),
+            stopped: AtomicBool::new(false)
+        })
+    }
+
+    pub fn status(&self, servo_id: Option<&str>) -> Result<ServoStatus, ServerError> {
+        let entry = self.find(servo_id)?;
+        let guard = self.states.lock()?;
+
+        Ok(ServoStatus {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            state: guard.get(&entry.id).cloned().unwrap_or_default()
+        })
+    }
+
+    pub fn check_servo(&self, servo_id: &str) -> Result<(), ServerError> {
+        self.find(Some(servo_id))?;
+        Ok(())
+    }
+
+    /// Moves the servo with configured speed, blocks until the angle is reached.
+    pub fn move_to(&self, servo_id: Option<&str>, angle: f32) -> Result<(), ServerError> {
+        let entry = self.find(servo_id)?;
+        let target = angle.clamp(DEGREE_START, DEGREE_END);
+
+        let mut servo = entry.servo.lock()?;
+        let position = self.position(&entry.id)?;
+
+        for position in steps(position, target, entry.speed) {
+            self.check_stopped(&mut servo)?;
+            servo.turn_to(position)?;
+            thread::sleep(STEP_INTERVAL);
+        }
+
+        self.check_stopped(&mut servo)?;
+        servo.turn_to(target)?;
+
+        self.update(&entry.id, |state| state.position = target)
+    }
+
+    pub fn move_to_preset(&self, servo_id: Option<&str>, name: &str) -> Result<(), ServerError> {
+        let entry = self.find(servo_id)?;
+        let angle = {
+            let guard = self.states.lock()?;
+            guard.get(&entry.id)
+                .and_then(|s| s.presets.get(name))
+                .copied()
+                .ok_or(LogicError::ServoPresetNotFound)?
+        };
+
+        self.move_to(Some(&entry.id), angle)
+    }
+
+    /// Saves the angle as preset, current position is used if angle is not set.
+    pub fn save_preset(&self, servo_id: Option<&str>, name: &str, angle: Option<f32>) -> Result<(), ServerError> {
+        let entry = self.find(servo_id)?;
+        self.update(&entry.id, |state| {
+            let angle = angle.unwrap_or(state.position).clamp(DEGREE_START, DEGREE_END);
+            state.presets.insert(name.to_owned(), angle);
+        })
+    }
+
+    pub fn remove_preset(&self, servo_id: Option<&str>, name: &str) -> Result<(), ServerError> {
+        let entry = self.find(servo_id)?;
+        let mut guard = self.states.lock()?;
+
+        guard.get_mut(&entry.id)
+            .and_then(|s| s.presets.remove(name))
+            .ok_or(LogicError::ServoPresetNotFound)?;
+
+        self.storage.save(SERVOS_STATE, &*guard)
+    }
+
+    pub fn calibrate(&self, servo_id: Option<&str>, calibration: ServoCalibration) -> Result<(), ServerError> {
+        if !calibration.is_valid() {
+            return Err(LogicError::InvalidServoCalibration.into());
+        }
+
+        let entry = self.find(servo_id)?;
+        let mut servo = entry.servo.lock()?;
+        servo.set_calibration(calibration);
+        servo.turn_to(self.position(&entry.id)?)?;
+
+        self.update(&entry.id, |state| state.calibration = Some(calibration))
+    }
+
+    /// Servo is disabled here as well, because safe state skips the servo which is locked by the move.
+    fn check_stopped(&self, servo: &mut Servo) -> Result<(), ServerError> {
+        if !self.stopped.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        servo.disable()?;
+        Err(io::Error::new(io::ErrorKind::Interrupted, "servos are stopped").into())
+    }
+
+    /// Legacy state is removed only after it is saved to the new state.
+    fn load_states(storage: &Storage) -> Result<HashMap<String, ServoState>, ServerError> {
+        let mut states : HashMap<String, ServoState> = storage.load(SERVOS_STATE)?;
+        if !storage.exists(LEGACY_SERVO_STATE) {
+            return Ok(states);
+        }
+
+        let legacy : ServoState = storage.load(LEGACY_SERVO_STATE)?;
+        if !states.contains_key(LEGACY_SERVO_ID) {
+            info!("servo state is migrated to servo {}", LEGACY_SERVO_ID);
+            states.insert(LEGACY_SERVO_ID.to_owned(), legacy);
+            storage.save(SERVOS_STATE, &states)?;
+        }
+
+        storage.remove(LEGACY_SERVO_STATE)?;
+        Ok(states)
+    }
+
+    fn position(&self, id: &str) -> Result<f32, ServerError> {
+        let guard = self.states.lock()?;
+        Ok(guard.get(id).map(|s| s.position).unwrap_or_default())
+    }
+
+    fn update<F: FnOnce(&mut ServoState)>(&self, id: &str, f: F) -> Result<(), ServerError> {
+        let mut guard = self.states.lock()?;
+        f(guard.entry(id.to_owned()).or_default());
+        self.storage.save(SERVOS_STATE, &*guard)
+    }
+
+    /// Id can be omitted only if a single servo is configured.
+    fn find(&self, id: Option<&str>) -> Result<&Entry, ServerError> {
+        let servo = match id {
+            Some(id) => self.servos.iter().find(|s| s.id.eq_ignore_ascii_case(id)),
+            None if self.servos.len() == 1 => self.servos.first(),
+            None => None
+        };
+
+        Ok(servo.ok_or(LogicError::ServoNotFound)?)
+    }
+}
+
+/// Intermediate positions of the move made every step interval, the target itself is not included.
+/// Servos without speed limit are turned to the target at once.
+fn steps(from: f32, to: f32, speed: f32) -> Vec<f32> {
+    let mut result = Vec::new();
+    if speed <= 0.0 {
+        return result;
+    }
+
+    let step = speed * STEP_INTERVAL.as_secs_f32();
+    let mut position = from;
+    while (to - position).abs() > step {
+        position += step.copysign(to - position);
+        result.push(position);
+    }
+
+    result
+}
+
+impl Actuator for ServoControl {
+    /// Moving servo holds its lock, so it is stopped by the flag on its next step.
+    fn safe_state(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+
+        for entry in &self.servos {
+            let mut servo = match entry.servo.try_lock() {
+                Ok(s) => s,
+                Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                Err(TryLockError::WouldBlock) => continue
+            };
+            if let Err(e) = servo.disable() {
+                error!("error on servo {} disable: {}", &entry.id, e);
+            }
+        }
+    }
+
+    /// Moves are limited by the angle range and speed, so nothing runs longer than allowed.
+    fn enforce_max_runtime(&self) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    fn storage(name: &str) -> Storage {
+        let path = env::temp_dir().join(format!("rpi_home_servos_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        Storage::new(path).unwrap()
+    }
+
+    fn state(position: f32) -> ServoState {
+        ServoState {
+            position,
+            presets: BTreeMap::from([("window".to_owned(), 45.0)]),
+            calibration: None
+        }
+    }
+
+    fn assert_positions(actual: Vec<f32>, expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}, expected {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 0.001, "{:?}, expected {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn interpolates_move_by_speed() {
+        // 50 degrees per second is 1 degree per step
+        assert_positions(steps(0.0, 4.5, 50.0), &[1.0, 2.0, 3.0, 4.0]);
+        assert_positions(steps(10.0, 5.5, 50.0), &[9.0, 8.0, 7.0, 6.0]);
+        assert_positions(steps(-90.0, -80.0, 250.0), &[-85.0]);
+        assert_positions(steps(0.0, 0.5, 50.0), &[]);
+        assert_positions(steps(-90.0, 90.0, 0.0), &[]);
+    }
+
+    #[test]
+    fn migrates_legacy_state() {
+        let storage = storage("migrate");
+        storage.save(LEGACY_SERVO_STATE, &state(30.0)).unwrap();
+
+        let states = ServoControl::load_states(&storage).unwrap();
+
+        assert_eq!(states[LEGACY_SERVO_ID].position, 30.0);
+        assert_eq!(states[LEGACY_SERVO_ID].presets.get("window"), Some(&45.0));
+        assert!(!storage.exists(LEGACY_SERVO_STATE));
+
+        let saved : HashMap<String, ServoState> = storage.load(SERVOS_STATE).unwrap();
+        assert_eq!(saved[LEGACY_SERVO_ID].position, 30.0);
+    }
+
+    #[test]
+    fn keeps_newer_state() {
+        let storage = storage("newer");
+        storage.save(LEGACY_SERVO_STATE, &state(30.0)).unwrap();
+        storage.save(SERVOS_STATE, &HashMap::from([(LEGACY_SERVO_ID.to_owned(), state(-10.0))])).unwrap();
+
+        let states = ServoControl::load_states(&storage).unwrap();
+
+        assert_eq!(states[LEGACY_SERVO_ID].position, -10.0);
+        assert!(!storage.exists(LEGACY_SERVO_STATE));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// Shared shutdown flag, background tasks finish their current iteration and stop once it is triggered.
+#[derive(Clone)]
+pub struct Shutdown {
+    sender: Arc<watch::Sender<bool>>,
+    receiver: watch::Receiver<bool>
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(false);
+        Shutdown {
+            sender: Arc::new(sender),
+            receiver
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Completes when shutdown is triggered.
+    pub async fn wait(&self) {
+        let mut receiver = self.receiver.clone();
+        // sender is kept in self, so the channel can not be closed
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
\ No newline at end of file
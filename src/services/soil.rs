@@ -0,0 +1,167 @@
+use serde::Serialize;
+
+use crate::config::{AdcConfig, AdcKind, SoilSensorConfig};
+use crate::server::server_error::{LogicError, ServerError};
+use crate::utils::adc::{Adc, Ads1115, Mcp3008, SimulatedAdc};
+
+pub struct Soil {
+    adc: Option<Box<dyn Adc>>,
+    sensors: Vec<SoilSensorConfig>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SoilMoisture {
+    id: String,
+    name: String,
+    raw: u16,
+    moisture: f32
+}
+
+impl SoilMoisture {
+    pub fn moisture(&self) -> f32 {
+        self.moisture
+    }
+}
+
+impl Soil {
+    pub fn new(adc: &Option<AdcConfig>, sensors: &[SoilSensorConfig]) -> Result<Self, ServerError> {
+        let adc : Option<Box<dyn Adc>> = match adc {
+            Some(config) => Some(match config.kind {
+                AdcKind::Mcp3008 => Box::new(Mcp3008::new()?),
+                AdcKind::Ads1115 => Box::new(Ads1115::new(config.address)?),
+                AdcKind::Simulated => Box::new(SimulatedAdc::new(config.simulated_values.clone()))
+            }),
+            None => None
+        };
+
+        if adc.is_none() && !sensors.is_empty() {
+            return Err(LogicError::AdcNotConfigured.into());
+        }
+
+        Ok(Soil {
+            adc,
+            sensors: sensors.to_vec()
+        })
+    }
+
+    pub fn moisture(&self, id: &str) -> Result<SoilMoisture, ServerError> {
+        let sensor = self.find(id)?;
+        self.read(sensor)
+    }
+
+    /// Checks that the sensor is configured, the adc is not read.
+    pub fn check_sensor(&self, id: &str) -> Result<(), ServerError> {
+        self.find(id)?;
+        Ok(())
+    }
+
+    pub fn all(&self) -> Result<Vec<SoilMoisture>, ServerError> {
+        self.sensors
+            .iter()
+            .map(|s| self.read(s))
+            .collect()
+    }
+
+    fn find(&self, id: &str) -> Result<&SoilSensorConfig, ServerError> {
+        let sensor = self.sensors
+            .iter()
+            .find(|s| s.id.eq_ignore_ascii_case(id));
+
+        Ok(sensor.ok_or(LogicError::SoilSensorNotFound)?)
+    }
+
+    fn read(&self, sensor: &SoilSensorConfig) -> Result<SoilMoisture, ServerError> {
+        let adc = self.adc.as_ref().ok_or(LogicError::AdcNotConfigured)?;
+        let raw = adc.read(sensor.channel)?;
+
+        Ok(SoilMoisture {
+            id: sensor.id.clone(),
+            name: sensor.name.clone(),
+            raw,
+            moisture: Self::to_percent(sensor, raw)
+        })
+    }
+
+    /// Converts raw value to percent using dry and wet calibration points.
+    fn to_percent(sensor: &SoilSensorConfig, raw: u16) -> f32 {
+        if sensor.dry == sensor.wet {
+            return 0.0;
+        }
+
+        let value = (raw as f32 - sensor.dry as f32) / (sensor.wet as f32 - sensor.dry as f32);
+        value.clamp(0.0, 1.0) * 100.0
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// Capacitive sensor on channel 0: the value goes down when the soil gets wet.
+    pub fn simulated_soil(raw: u16) -> Soil {
+        let adc = AdcConfig {
+            kind: AdcKind::Simulated,
+            address: 0,
+            simulated_values: HashMap::from([(0, raw)])
+        };
+        let sensor = SoilSensorConfig {
+            id: "pot".to_owned(),
+            name: "Pot".to_owned(),
+            channel: 0,
+            dry: 800,
+            wet: 400
+        };
+
+        Soil::new(&Some(adc), &[sensor]).unwrap()
+    }
+
+    #[test]
+    fn maps_calibration_points() {
+        assert_eq!(simulated_soil(800).moisture("pot").unwrap().moisture(), 0.0);
+        assert_eq!(simulated_soil(600).moisture("pot").unwrap().moisture(), 50.0);
+        assert_eq!(simulated_soil(400).moisture("pot").unwrap().moisture(), 100.0);
+    }
+
+    #[test]
+    fn clamps_values_outside_calibration() {
+        assert_eq!(simulated_soil(1000).moisture("pot").unwrap().moisture(), 0.0);
+        assert_eq!(simulated_soil(100).moisture("pot").unwrap().moisture(), 100.0);
+    }
+
+    #[test]
+    fn maps_rising_calibration() {
+        let sensor = SoilSensorConfig {
+            id: "pot".to_owned(),
+            name: "Pot".to_owned(),
+            channel: 0,
+            dry: 100,
+            wet: 900
+        };
+
+        assert_eq!(Soil::to_percent(&sensor, 300), 25.0);
+        assert_eq!(Soil::to_percent(&SoilSensorConfig { wet: 100, ..sensor }, 300), 0.0);
+    }
+
+    #[test]
+    fn checks_sensor_without_read() {
+        let soil = simulated_soil(600);
+
+        assert!(soil.check_sensor("POT").is_ok());
+        assert!(matches!(soil.check_sensor("other"), Err(ServerError::Logic(LogicError::SoilSensorNotFound))));
+    }
+
+    #[test]
+    fn requires_adc_for_sensors() {
+        let sensor = SoilSensorConfig {
+            id: "pot".to_owned(),
+            name: "Pot".to_owned(),
+            channel: 0,
+            dry: 800,
+            wet: 400
+        };
+
+        assert!(matches!(Soil::new(&None, &[sensor]), Err(ServerError::Logic(LogicError::AdcNotConfigured))));
+    }
+}
\ No newline at end of file
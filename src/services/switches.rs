@@ -73,6 +73,26 @@ impl Switches {
         }
     }
 
+    pub fn states(&self) -> Result<Vec<(String, bool)>, ServerError> {
+        let guard = self.state.lock()?;
+        Ok(guard.switches
+            .iter()
+            .map(|s| (s.name.clone(), s.enabled))
+            .collect())
+    }
+
+    /// Switches which reported their command address.
+    pub fn devices(&self) -> Result<Vec<(String, String, u16)>, ServerError> {
+        let guard = self.state.lock()?;
+        Ok(guard.switches
+            .iter()
+            .filter_map(|s| match (&s.ip, s.port) {
+                (Some(ip), Some(port)) => Some((s.name.clone(), ip.clone(), port)),
+                _ => None
+            })
+            .collect())
+    }
+
     fn find_mut<'a>(state: &'a mut State, name: &str) -> Option<&'a mut Switch> {
         state.switches
             .iter_mut()
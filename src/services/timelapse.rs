@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime};
+use image::GrayImage;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use crate::config::TimelapseConfig;
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::camera_rig::CameraRig;
+use crate::services::camera_service::{CameraService, Resolution};
+use crate::services::shutdown::Shutdown;
+use crate::utils::image_metrics::{self, ImageMetrics, Region};
+use crate::utils::time::unix_now;
+
+const FRAME_EXTENSION : &str = "jpg";
+const METRICS_EXTENSION : &str = "json";
+const TIME_FORMAT : &str = "%H:%M";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FrameInfo {
+    pub time: u64,
+    /// Index of the frame among frames of the pose captured within the same second.
+    pub sequence: u32,
+    pub pose: Option<String>,
+    pub size: u64
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FrameMetrics {
+    pub time: u64,
+    pub sequence: u32,
+    pub pose: Option<String>,
+    #[serde(flatten)]
+    pub metrics: ImageMetrics
+}
+
+/// Photos captured on schedule, each frame is stored as `<unix time>.jpg`
+/// with its image metrics in `<unix time>.json`, following frames of the same second
+/// get `-<sequence>` suffix. Frames of camera rig poses are stored in the same way
+/// in `<pose>` subdirectories. Metrics files are counted in the quota.
+pub struct Timelapse {
+    path: PathBuf,
+    interval: Duration,
+    window: Option<(NaiveTime, NaiveTime)>,
+    quota_bytes: u64,
+    roi: Region,
+    poses: Vec<String>,
+    /// Serializes saving and rotation with readers, keeps fingerprint of the last frame of every pose.
+    lock: Mutex<HashMap<Option<String>, GrayImage>>
+}
+
+impl Timelapse {
+    pub fn new(config: &TimelapseConfig) -> Result<Self, io::Error> {
+        fs::create_dir_all(&config.path)?;
+
+        let window = match (&config.start, &config.end) {
+            (Some(start), Some(end)) => Some((parse_time(start)?, parse_time(end)?)),
+            (None, None) => None,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "time-lapse window needs both start and end"))
+        };
+
+        for pose in &config.poses {
+            // pose name is used as directory name
+            if pose.is_empty() || !pose.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid time-lapse pose name {}", pose)));
+            }
+            fs::create_dir_all(PathBuf::from(&config.path).join(pose))?;
+        }
+
+        Ok(Timelapse {
+            path: PathBuf::from(&config.path),
+            interval: Duration::from_secs(config.interval_seconds.max(1)),
+            window,
+            quota_bytes: config.quota_mb * 1024 * 1024,
+            roi: config.roi,
+            poses: config.poses.clone(),
+            lock: Mutex::new(HashMap::new())
+        })
+    }
+
+    pub fn poses(&self) -> &[String] {
+        &self.poses
+    }
+
+    /// Poses are captured with the rig, it should be set if any pose is configured.
+    pub fn start(timelapse: &Arc<Timelapse>, camera: &Arc<CameraService>, rig: Option<Arc<CameraRig>>, shutdown: &Shutdown) -> JoinHandle<()> {
+        let timelapse = timelapse.clone();
+        let camera = camera.clone();
+        let shutdown = shutdown.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(timelapse.interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.wait() => break
+                }
+
+                if !timelapse.is_in_window() {
+                    continue;
+                }
+
+                match &rig {
+                    Some(rig) if !timelapse.poses.is_empty() => {
+                        for pose in &timelapse.poses {
+                            let photo = CameraRig::capture_at(rig, pose).await;
+                            Timelapse::save_photo(&timelapse, Some(pose.clone()), photo).await;
+                        }
+                    },
+                    _ => {
+                        let photo = camera.capture(Resolution::Full).await.map_err(ServerError::from);
+                        Timelapse::save_photo(&timelapse, None, photo).await;
+                    }
+                }
+            }
+        })
+    }
+
+    async fn save_photo(timelapse: &Arc<Timelapse>, pose: Option<String>, photo: Result<Vec<u8>, ServerError>) {
+        let photo = match photo {
+            Ok(p) => p,
+            Err(e) => {
+                error!("error on time-lapse capture: {}", e);
+                return;
+            }
+        };
+
+        let timelapse = timelapse.clone();
+        let result = tokio::task::spawn_blocking(move || timelapse.save(pose.as_deref(), &photo)).await;
+        match result {
+            Ok(Err(e)) => error!("error on time-lapse frame save: {}", e),
+            Err(e) => error!("time-lapse save task failed: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+
+    /// The photo is decoded for metrics without the lock, so readers are not blocked by analysis.
+    pub fn save(&self, pose: Option<&str>, photo: &[u8]) -> Result<(), ServerError> {
+        let time = unix_now();
+        let key = pose.map(|p| p.to_owned());
+
+        let previous = self.lock.lock()?.get(&key).cloned();
+        let analysis = image_metrics::analyze(photo, &self.roi, previous.as_ref());
+
+        let mut guard = self.lock.lock()?;
+        let mut sequence = 0;
+        while self.frame_path(pose, time, sequence).exists() {
+            sequence += 1;
+        }
+
+        fs::write(self.frame_path(pose, time, sequence), photo)?;
+        info!("time-lapse frame {}-{} {:?} saved ({} bytes)", time, sequence, pose, photo.len());
+
+        match analysis {
+            Ok(analysis) => {
+                fs::write(self.metrics_path(pose, time, sequence), serde_json::to_vec(&analysis.metrics)?)?;
+                guard.insert(key, analysis.fingerprint);
+            },
+            Err(e) => {
+                error!("error on time-lapse frame {}-{} analysis: {}", time, sequence, e);
+                guard.remove(&key);
+            }
+        }
+
+        self.rotate()
+    }
+
+    /// Returns metrics of stored frames of the pose captured after `since`, oldest first.
+    pub fn metrics(&self, pose: Option<&str>, since: Option<u64>) -> Result<Vec<FrameMetrics>, ServerError> {
+        self.check_pose(pose)?;
+
+        let _guard = self.lock.lock()?;
+
+        let mut result = Vec::new();
+        for frame in self.list()? {
+            if frame.pose.as_deref() != pose || since.map(|s| frame.time <= s).unwrap_or(false) {
+                continue;
+            }
+
+            let data = match fs::read(self.metrics_path(pose, frame.time, frame.sequence)) {
+                Ok(d) => d,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into())
+            };
+
+            result.push(FrameMetrics {
+                time: frame.time,
+                sequence: frame.sequence,
+                pose: frame.pose,
+                metrics: serde_json::from_slice(&data)?
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Returns stored frames of all poses, oldest first.
+    pub fn frames(&self) -> Result<Vec<FrameInfo>, ServerError> {
+        let _guard = self.lock.lock()?;
+        self.list()
+    }
+
+    pub fn frame(&self, pose: Option<&str>, time: u64, sequence: u32) -> Result<Vec<u8>, ServerError> {
+        self.check_pose(pose)?;
+
+        let _guard = self.lock.lock()?;
+        match fs::read(self.frame_path(pose, time, sequence)) {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(LogicError::FrameNotFound.into()),
+            Err(e) => Err(e.into())
+        }
+    }
+
+    /// Returns the newest stored frame of the pose, the camera is not used.
+    pub fn latest(&self, pose: Option<&str>) -> Result<(FrameInfo, Vec<u8>), ServerError> {
+        let frame = self.frames()?
+            .into_iter()
+            .rev()
+            .find(|f| f.pose.as_deref() == pose)
+            .ok_or(LogicError::FrameNotFound)?;
+
+        let data = self.frame(pose, frame.time, frame.sequence)?;
+        Ok((frame, data))
+    }
+
+    fn check_pose(&self, pose: Option<&str>) -> Result<(), ServerError> {
+        match pose {
+            Some(pose) if !self.poses.iter().any(|p| p == pose) => Err(LogicError::CameraPoseNotFound.into()),
+            _ => Ok(())
+        }
+    }
+
+    fn is_in_window(&self) -> bool {
+        let (start, end) = match self.window {
+            Some(w) => w,
+            None => return true
+        };
+
+        let now = Local::now().naive_local().time();
+        if start <= end {
+            start <= now && now < end
+        } else {
+            start <= now || now < end
+        }
+    }
+
+    fn rotate(&self) -> Result<(), ServerError> {
+        let mut frames = Vec::new();
+        for frame in self.list()? {
+            let metrics_size = match fs::metadata(self.metrics_path(frame.pose.as_deref(), frame.time, frame.sequence)) {
+                Ok(m) => m.len(),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+                Err(e) => return Err(e.into())
+            };
+            frames.push((frame.size + metrics_size, frame));
+        }
+
+        let mut total : u64 = frames.iter().map(|(size, _)| size).sum();
+        for (size, frame) in frames {
+            if total <= self.quota_bytes {
+                break;
+            }
+
+            let pose = frame.pose.as_deref();
+            fs::remove_file(self.frame_path(pose, frame.time, frame.sequence))?;
+            if let Err(e) = fs::remove_file(self.metrics_path(pose, frame.time, frame.sequence)) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    return Err(e.into());
+                }
+            }
+
+            total -= size;
+            info!("time-lapse frame {}-{} {:?} removed by quota", frame.time, frame.sequence, pose);
+        }
+
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<FrameInfo>, ServerError> {
+        let mut frames = Vec::new();
+        self.list_dir(None, &mut frames)?;
+        for pose in &self.poses {
+            self.list_dir(Some(pose), &mut frames)?;
+        }
+
+        frames.sort_by_key(|f| (f.time, f.sequence));
+        Ok(frames)
+    }
+
+    fn list_dir(&self, pose: Option<&str>, frames: &mut Vec<FrameInfo>) -> Result<(), ServerError> {
+        for entry in fs::read_dir(self.dir(pose))? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(FRAME_EXTENSION) {
+                continue;
+            }
+
+            let (time, sequence) = match path.file_stem().and_then(|s| s.to_str()).and_then(parse_name) {
+                Some(t) => t,
+                None => continue
+            };
+
+            frames.push(FrameInfo {
+                time,
+                sequence,
+                pose: pose.map(|p| p.to_owned()),
+                size: fs::metadata(&path)?.len()
+            });
+        }
+
+        Ok(())
+    }
+
+    fn dir(&self, pose: Option<&str>) -> PathBuf {
+        match pose {
+            Some(pose) => self.path.join(pose),
+            None => self.path.clone()
+        }
+    }
+
+    fn frame_path(&self, pose: Option<&str>, time: u64, sequence: u32) -> PathBuf {
+        self.dir(pose).join(format!("{}.{}", name(time, sequence), FRAME_EXTENSION))
+    }
+
+    fn metrics_path(&self, pose: Option<&str>, time: u64, sequence: u32) -> PathBuf {
+        self.dir(pose).join(format!("{}.{}", name(time, sequence), METRICS_EXTENSION))
+    }
+}
+
+/// The first frame of the second keeps plain `<unix time>` name, so older frames are listed as before.
+fn name(time: u64, sequence: u32) -> String {
+    match sequence {
+        0 => time.to_string(),
+        s => format!("{}-{}", time, s)
+    }
+}
+
+fn parse_name(name: &str) -> Option<(u64, u32)> {
+    match name.split_once('-') {
+        Some((time, sequence)) => Some((time.parse().ok()?, sequence.parse().ok()?)),
+        None => Some((name.parse().ok()?, 0))
+    }
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime, io::Error> {
+    NaiveTime::parse_from_str(time, TIME_FORMAT)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid time-lapse time {}: {}", time, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use crate::utils::jpeg;
+
+    fn timelapse(name: &str, poses: &[&str], quota_bytes: u64) -> Timelapse {
+        let path = env::temp_dir().join(format!("rpi_home_timelapse_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let config = TimelapseConfig {
+            path: path.to_string_lossy().into_owned(),
+            interval_seconds: 60,
+            start: None,
+            end: None,
+            quota_mb: 0,
+            roi: Region::default(),
+            poses: poses.iter().map(|p| p.to_string()).collect()
+        };
+
+        let mut timelapse = Timelapse::new(&config).unwrap();
+        timelapse.quota_bytes = quota_bytes;
+        timelapse
+    }
+
+    fn files(timelapse: &Timelapse, extension: &str) -> usize {
+        fs::read_dir(&timelapse.path).unwrap()
+            .filter(|e| e.as_ref().unwrap().path().extension().and_then(|e| e.to_str()) == Some(extension))
+            .count()
+    }
+
+    #[test]
+    fn keeps_frames_of_the_same_second() {
+        let timelapse = timelapse("sequence", &["left"], u64::MAX);
+        let photo = jpeg::simulated_frame(0, 64, 48).unwrap();
+
+        timelapse.save(None, &photo).unwrap();
+        timelapse.save(None, &photo).unwrap();
+        timelapse.save(Some("left"), &photo).unwrap();
+
+        // frames may cross a second boundary, so only ordering and count are checked
+        let frames = timelapse.frames().unwrap();
+        assert_eq!(frames.len(), 3);
+        let own : Vec<_> = frames.iter().filter(|f| f.pose.is_none()).map(|f| (f.time, f.sequence)).collect();
+        assert_eq!(own.len(), 2);
+        assert!(own[0] < own[1]);
+        assert_eq!(timelapse.metrics(None, None).unwrap().len(), 2);
+
+        let (latest, data) = timelapse.latest(Some("left")).unwrap();
+        assert_eq!(latest.pose.as_deref(), Some("left"));
+        assert_eq!(data, photo);
+        assert!(timelapse.latest(Some("right")).is_err());
+    }
+
+    #[test]
+    fn lists_frames_by_time_and_sequence() {
+        let timelapse = timelapse("listing", &[], u64::MAX);
+        for name in ["200-1", "100", "200", "bad", "200-x"] {
+            fs::write(timelapse.path.join(format!("{}.{}", name, FRAME_EXTENSION)), [0u8; 4]).unwrap();
+        }
+        fs::write(timelapse.path.join("300.json"), b"{}").unwrap();
+
+        let frames : Vec<_> = timelapse.frames().unwrap().iter().map(|f| (f.time, f.sequence, f.size)).collect();
+        assert_eq!(frames, vec![(100, 0, 4), (200, 0, 4), (200, 1, 4)]);
+        assert_eq!(timelapse.frame(None, 200, 1).unwrap(), vec![0u8; 4]);
+        assert!(timelapse.frame(None, 300, 0).is_err());
+    }
+
+    #[test]
+    fn rotates_frames_with_metrics_by_quota() {
+        let photo = jpeg::simulated_frame(0, 64, 48).unwrap();
+        // three frames fit the quota only if metrics files are not counted
+        let timelapse = timelapse("quota", &[], 3 * photo.len() as u64);
+        for _ in 0..3 {
+            timelapse.save(None, &photo).unwrap();
+        }
+
+        assert_eq!(files(&timelapse, FRAME_EXTENSION), 2);
+        assert_eq!(files(&timelapse, METRICS_EXTENSION), 2);
+
+        let frames : Vec<_> = timelapse.frames().unwrap().iter().map(|f| (f.time, f.sequence)).collect();
+        let metrics : Vec<_> = timelapse.metrics(None, None).unwrap().iter().map(|m| (m.time, m.sequence)).collect();
+        assert_eq!(frames, metrics);
+    }
+}
\ No newline at end of file
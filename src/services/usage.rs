@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ConditionerConfig, WaterPumpConfig};
+use crate::server::server_error::ServerError;
+use crate::services::climate::Conditioner;
+use crate::utils::storage::Storage;
+use crate::utils::time::unix_now;
+
+const USAGE_STATE : &str = "usage";
+const DATE_FORMAT : &str = "%Y-%m-%d";
+const HISTORY_DAYS : usize = 400;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePeriod {
+    #[default]
+    Day,
+    Week,
+    Month
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct PumpRuns {
+    seconds: f64,
+    ml: f64
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct DayUsage {
+    conditioners: BTreeMap<String, f64>,
+    pumps: BTreeMap<String, PumpRuns>
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConditionerUsage {
+    id: String,
+    runtime_seconds: f64,
+    kwh: Option<f64>
+}
+
+#[derive(Serialize, Debug)]
+pub struct PumpUsage {
+    id: String,
+    runtime_seconds: f64,
+    litres: f64,
+    kwh: Option<f64>
+}
+
+#[derive(Serialize, Debug)]
+pub struct UsageReport {
+    period: String,
+    conditioners: Vec<ConditionerUsage>,
+    pumps: Vec<PumpUsage>
+}
+
+struct State {
+    /// Usage by local date, keys are sorted chronologically.
+    days: BTreeMap<String, DayUsage>,
+    /// Time of the last state update of running conditioners.
+    running: HashMap<String, u64>
+}
+
+pub struct Usage {
+    conditioner_watts: HashMap<String, f32>,
+    pump_watts: HashMap<String, f32>,
+    storage: Arc<Storage>,
+    state: Mutex<State>
+}
+
+impl Usage {
+    pub fn new(conditioners: &[ConditionerConfig], pumps: &[WaterPumpConfig], storage: &Arc<Storage>) -> Result<Self, ServerError> {
+        Ok(Usage {
+            conditioner_watts: conditioners
+                .iter()
+                .filter_map(|c| c.power_watts.map(|w| (c.id.clone(), w)))
+                .collect(),
+            pump_watts: pumps
+                .iter()
+                .filter_map(|p| p.power_watts.map(|w| (p.id.clone(), w)))
+                .collect(),
+            storage: storage.clone(),
+            state: Mutex::new(State {
+                days: storage.load(USAGE_STATE)?,
+                running: HashMap::new()
+            })
+        })
+    }
+
+    /// Accounts time since the previous call to conditioners which were enabled since then,
+    /// it is called on every state change and periodically by the climate schedule.
+    pub fn record_conditioners(&self, conditioners: &[Conditioner]) -> Result<(), ServerError> {
+        let now = unix_now();
+        let mut guard = self.state.lock()?;
+        let mut changed = false;
+
+        for c in conditioners {
+            if let Some(since) = guard.running.remove(c.id()) {
+                Usage::add_runtime(&mut guard.days, c.id(), since, now);
+                changed = true;
+            }
+
+            if c.is_enabled() {
+                guard.running.insert(c.id().to_owned(), now);
+            }
+        }
+
+        if changed {
+            self.save(&mut guard)?;
+        }
+
+        Ok(())
+    }
+
+    /// Accounts running conditioners up to now and saves usage, called on shutdown
+    /// because running time is kept only in memory.
+    pub fn flush(&self) -> Result<(), ServerError> {
+        let now = unix_now();
+        let mut guard = self.state.lock()?;
+
+        let running : Vec<(String, u64)> = guard.running.drain().collect();
+        for (id, since) in running {
+            Usage::add_runtime(&mut guard.days, &id, since, now);
+        }
+
+        self.save(&mut guard)
+    }
+
+    pub fn record_pump(&self, pump_id: &str, duration: Duration, flow_ml_per_second: Option<f32>) -> Result<(), ServerError> {
+        let mut guard = self.state.lock()?;
+
+        let runs = Usage::today(&mut guard.days).pumps.entry(pump_id.to_owned()).or_default();
+        runs.seconds += duration.as_secs_f64();
+        runs.ml += flow_ml_per_second.unwrap_or(0.0) as f64 * duration.as_secs_f64();
+
+        self.save(&mut guard)
+    }
+
+    /// Returns usage for `count` last periods, newest first.
+    pub fn report(&self, period: UsagePeriod, count: usize) -> Result<Vec<UsageReport>, ServerError> {
+        let guard = self.state.lock()?;
+
+        let mut grouped: Vec<(String, DayUsage)> = Vec::new();
+        for (date, day) in guard.days.iter().rev() {
+            let key = match NaiveDate::parse_from_str(date, DATE_FORMAT) {
+                Ok(d) => Usage::period_key(period, d),
+                Err(_) => continue
+            };
+
+            if grouped.last().map(|(k, _)| k != &key).unwrap_or(true) {
+                if grouped.len() == count {
+                    break;
+                }
+                grouped.push((key, DayUsage::default()));
+            }
+
+            if let Some((_, total)) = grouped.last_mut() {
+                for (id, seconds) in &day.conditioners {
+                    *total.conditioners.entry(id.clone()).or_default() += seconds;
+                }
+                for (id, runs) in &day.pumps {
+                    let total = total.pumps.entry(id.clone()).or_default();
+                    total.seconds += runs.seconds;
+                    total.ml += runs.ml;
+                }
+            }
+        }
+
+        Ok(grouped
+            .into_iter()
+            .map(|(key, usage)| self.to_report(key, usage))
+            .collect())
+    }
+
+    fn to_report(&self, period: String, usage: DayUsage) -> UsageReport {
+        UsageReport {
+            period,
+            conditioners: usage.conditioners
+                .into_iter()
+                .map(|(id, seconds)| ConditionerUsage {
+                    kwh: self.conditioner_watts.get(&id).map(|w| kwh(*w, seconds)),
+                    id,
+                    runtime_seconds: seconds
+                })
+                .collect(),
+            pumps: usage.pumps
+                .into_iter()
+                .map(|(id, runs)| PumpUsage {
+                    kwh: self.pump_watts.get(&id).map(|w| kwh(*w, runs.seconds)),
+                    id,
+                    runtime_seconds: runs.seconds,
+                    litres: runs.ml / 1000.0
+                })
+                .collect()
+        }
+    }
+
+    fn period_key(period: UsagePeriod, date: NaiveDate) -> String {
+        match period {
+            UsagePeriod::Day => date.format(DATE_FORMAT).to_string(),
+            UsagePeriod::Week => {
+                let week = date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            },
+            UsagePeriod::Month => date.format("%Y-%m").to_string()
+        }
+    }
+
+    fn add_runtime(days: &mut BTreeMap<String, DayUsage>, id: &str, since: u64, now: u64) {
+        for (date, seconds) in split_by_day(since, now) {
+            *days.entry(date).or_default().conditioners.entry(id.to_owned()).or_default() += seconds;
+        }
+    }
+
+    fn today(days: &mut BTreeMap<String, DayUsage>) -> &mut DayUsage {
+        let today = Local::now().naive_local().date().format(DATE_FORMAT).to_string();
+        days.entry(today).or_default()
+    }
+
+    fn save(&self, state: &mut State) -> Result<(), ServerError> {
+        while state.days.len() > HISTORY_DAYS {
+            let oldest = match state.days.keys().next() {
+                Some(k) => k.clone(),
+                None => break
+            };
+            state.days.remove(&oldest);
+        }
+
+        self.storage.save(USAGE_STATE, &state.days)
+    }
+}
+
+fn kwh(watts: f32, seconds: f64) -> f64 {
+    watts as f64 * seconds / 3600.0 / 1000.0
+}
+
+/// Splits the interval at local midnights, so runtime is accounted to the days when it happened.
+fn split_by_day(since: u64, now: u64) -> Vec<(String, f64)> {
+    let mut result = Vec::new();
+    let mut start = since;
+
+    while start < now {
+        let date = match Local.timestamp_opt(start as i64, 0).earliest() {
+            Some(t) => t.date_naive(),
+            None => break
+        };
+
+        let next_day = date.succ_opt()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .and_then(|t| Local.from_local_datetime(&t).earliest())
+            .map(|t| t.timestamp() as u64)
+            .unwrap_or(now);
+        let end = next_day.clamp(start + 1, now);
+
+        result.push((date.format(DATE_FORMAT).to_string(), (end - start) as f64));
+        start = end;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(day: u32, hour: u32) -> u64 {
+        Local.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap().timestamp() as u64
+    }
+
+    #[test]
+    fn splits_runtime_at_midnight() {
+        assert_eq!(split_by_day(local(1, 23), local(2, 1)), vec![
+            ("2026-03-01".to_owned(), 3600.0),
+            ("2026-03-02".to_owned(), 3600.0)
+        ]);
+        assert_eq!(split_by_day(local(1, 22), local(3, 2)), vec![
+            ("2026-03-01".to_owned(), 7200.0),
+            ("2026-03-02".to_owned(), 86400.0),
+            ("2026-03-03".to_owned(), 7200.0)
+        ]);
+    }
+
+    #[test]
+    fn keeps_interval_within_day() {
+        assert_eq!(split_by_day(local(1, 10), local(1, 12)), vec![("2026-03-01".to_owned(), 7200.0)]);
+        assert!(split_by_day(local(1, 12), local(1, 12)).is_empty());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,330 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::config::{WaterPumpConfig, WaterSensorConfig};
+use crate::server::server_error::{LogicError, ServerError};
+use crate::services::safety::Actuator;
+use crate::services::usage::Usage;
+use crate::utils::storage::Storage;
+use crate::utils::water_pump::WaterPump;
+use crate::utils::water_sensor::WaterSensor;
+
+const FLOW_RATES_STATE : &str = "flow_rates";
+
+pub struct Water {
+    pumps: Vec<Pump>,
+    sensors: Vec<Sensor>,
+    storage: Arc<Storage>,
+    usage: Arc<Usage>,
+    calibration: Mutex<Calibration>
+}
+
+struct Pump {
+    id: String,
+    water_sensor_id: Option<String>,
+    flow_ml_per_second: Option<f32>,
+    max_runtime: Duration,
+    runs: AtomicU64,
+    run_millis: AtomicU64,
+    pump: WaterPump
+}
+
+/// Pump run for the requested volume, the volume is reduced if the run is capped at the max runtime.
+pub struct VolumeRun {
+    pub duration: Duration,
+    pub volume_ml: f32
+}
+
+/// Pump runs since the server start.
+pub struct PumpStats {
+    pub id: String,
+    pub runs: u64,
+    pub seconds: f64,
+    pub running: bool
+}
+
+struct Sensor {
+    id: String,
+    sensor: WaterSensor,
+    /// Result of the last read, sensors are powered only while they are read.
+    last_level: Mutex<Option<bool>>
+}
+
+struct Calibration {
+    flow_rates: HashMap<String, f32>,
+    pending: HashMap<String, Duration>
+}
+
+impl Water {
+    pub fn new(pumps: &[WaterPumpConfig], sensors: &[WaterSensorConfig], storage: &Arc<Storage>, usage: &Arc<Usage>) -> Result<Self, ServerError> {
+        check_unique(pumps.iter().map(|p| p.id.as_str()))?;
+        check_unique(sensors.iter().map(|s| s.id.as_str()))?;
+
+        let mut result = Water {
+            pumps: Vec::with_capacity(pumps.len()),
+            sensors: Vec::with_capacity(sensors.len()),
+            storage: storage.clone(),
+            usage: usage.clone(),
+            calibration: Mutex::new(Calibration {
+                flow_rates: storage.load(FLOW_RATES_STATE)?,
+                pending: HashMap::new()
+            })
+        };
+
+        for config in sensors {
+            info!("water sensor {} ({}): power pin {}, in pin {}", &config.id, &config.name, config.power_pin, config.in_pin);
+            result.sensors.push(Sensor {
+                id: config.id.clone(),
+                sensor: WaterSensor::new(config.power_pin, config.in_pin)?,
+                last_level: Mutex::new(None)
+            });
+        }
+
+        for config in pumps {
+            if let Some(sensor_id) = &config.water_sensor_id {
+                result.find_sensor(Some(sensor_id))?;
+            }
+
+            info!("water pump {} ({}): pin {}", &config.id, &config.name, config.pin);
+            result.pumps.push(Pump {
+                id: config.id.clone(),
+                water_sensor_id: config.water_sensor_id.clone(),
+                flow_ml_per_second: config.flow_ml_per_second,
+                max_runtime: Duration::from_secs(config.max_runtime_seconds),
+                runs: AtomicU64::new(0),
+                run_millis: AtomicU64::new(0),
+                pump: WaterPump::new(config.pin)?
+            });
+        }
+
+        Ok(result)
+    }
+
+    pub fn is_enough(&self, sensor_id: Option<&str>) -> Result<bool, ServerError> {
+        let sensor = self.find_sensor(sensor_id)?;
+        let enough = sensor.sensor.is_enough()?;
+        *sensor.last_level.lock()? = Some(enough);
+        Ok(enough)
+    }
+
+    pub fn is_enough_for_pump(&self, pump_id: Option<&str>) -> Result<bool, ServerError> {
+        let pump = self.find_pump(pump_id)?;
+        match &pump.water_sensor_id {
+            Some(sensor_id) => self.is_enough(Some(sensor_id)),
+            None => Ok(true)
+        }
+    }
+
+    /// Returns calibrated flow rate of the pump, falls back to the configured one.
+    pub fn flow_rate(&self, pump_id: Option<&str>) -> Result<Option<f32>, ServerError> {
+        let pump = self.find_pump(pump_id)?;
+        let guard = self.calibration.lock()?;
+
+        Ok(guard.flow_rates
+            .get(&pump.id)
+            .copied()
+            .or(pump.flow_ml_per_second))
+    }
+
+    /// Duration is capped at the pump max runtime, so a huge volume results in the longest allowed run.
+    pub fn duration_for_volume(&self, pump_id: Option<&str>, volume_ml: f32) -> Result<VolumeRun, ServerError> {
+        let pump = self.find_pump(pump_id)?;
+        let flow = self.flow_rate(Some(&pump.id))?
+            .filter(|f| f.is_finite() && *f > 0.0)
+            .ok_or(LogicError::PumpNotCalibrated)?;
+
+        if !volume_ml.is_finite() || volume_ml < 0.0 {
+            return Err(LogicError::InvalidWaterVolume.into());
+        }
+
+        let duration = Duration::try_from_secs_f32(volume_ml / flow)
+            .map_err(|_| LogicError::InvalidWaterVolume)?;
+
+        if duration > pump.max_runtime {
+            let capped_ml = pump.max_runtime.as_secs_f32() * flow;
+            warn!("water pump {} run for {}ml is capped at {}s, {}ml", &pump.id, volume_ml, pump.max_runtime.as_secs(), capped_ml);
+            return Ok(VolumeRun {
+                duration: pump.max_runtime,
+                volume_ml: capped_ml
+            });
+        }
+
+        Ok(VolumeRun {
+            duration,
+            volume_ml
+        })
+    }
+
+    /// Blocks the thread while the pump runs.
+    pub fn enable_pump(&self, pump_id: Option<&str>, duration: Duration) -> Result<(), ServerError> {
+        let pump = self.find_pump(pump_id)?;
+        if duration > pump.max_runtime {
+            return Err(LogicError::PumpRuntimeExceeded.into());
+        }
+
+        let flow = self.flow_rate(Some(&pump.id))?;
+
+        info!("enabling water pump {} for {}s", &pump.id, duration.as_secs_f32());
+        pump.pump.enable(duration)?;
+        pump.runs.fetch_add(1, Ordering::Relaxed);
+        pump.run_millis.fetch_add(duration.as_millis() as u64, Ordering::Relaxed);
+
+        // water is already poured, so accounting errors do not fail the watering
+        if let Err(e) = self.usage.record_pump(&pump.id, duration, flow) {
+            error!("error on water pump {} usage record: {}", &pump.id, e);
+        }
+        Ok(())
+    }
+
+    /// Runs the pump for a fixed time, volume poured during this run should be passed to `finish_calibration`.
+    pub fn start_calibration(&self, pump_id: Option<&str>, duration: Duration) -> Result<(), ServerError> {
+        let pump = self.find_pump(pump_id)?;
+        if duration.is_zero() {
+            return Err(LogicError::InvalidCalibration.into());
+        }
+
+        self.enable_pump(Some(&pump.id), duration)?;
+
+        let mut guard = self.calibration.lock()?;
+        guard.pending.insert(pump.id.clone(), duration);
+
+        Ok(())
+    }
+
+    pub fn finish_calibration(&self, pump_id: Option<&str>, volume_ml: f32) -> Result<f32, ServerError> {
+        let pump = self.find_pump(pump_id)?;
+        if !volume_ml.is_finite() || volume_ml <= 0.0 {
+            return Err(LogicError::InvalidCalibration.into());
+        }
+
+        let mut guard = self.calibration.lock()?;
+
+        let duration = guard.pending
+            .remove(&pump.id)
+            .ok_or(LogicError::CalibrationNotStarted)?;
+
+        let flow = volume_ml / duration.as_secs_f32();
+        info!("water pump {} calibrated: {}ml/s", &pump.id, flow);
+
+        guard.flow_rates.insert(pump.id.clone(), flow);
+        self.storage.save(FLOW_RATES_STATE, &guard.flow_rates)?;
+
+        Ok(flow)
+    }
+
+    pub fn pump_stats(&self) -> Vec<PumpStats> {
+        self.pumps
+            .iter()
+            .map(|p| PumpStats {
+                id: p.id.clone(),
+                runs: p.runs.load(Ordering::Relaxed),
+                seconds: p.run_millis.load(Ordering::Relaxed) as f64 / 1000.0,
+                running: p.pump.running_for().is_some()
+            })
+            .collect()
+    }
+
+    /// Returns levels of the last reads before watering or by request, sensors are not read here,
+    /// level is not set for sensors which were not read since the server start.
+    pub fn water_levels(&self) -> Result<Vec<(String, Option<bool>)>, ServerError> {
+        let mut levels = Vec::with_capacity(self.sensors.len());
+        for s in &self.sensors {
+            levels.push((s.id.clone(), *s.last_level.lock()?));
+        }
+        Ok(levels)
+    }
+
+    /// Returns the number of GPIO pins used by pumps and sensors.
+    pub fn check_gpio(&self) -> Result<usize, ServerError> {
+        WaterPump::check_gpio()?;
+        Ok(self.pumps.len() + self.sensors.len() * 2)
+    }
+
+    /// Returns id of the pump, which is used when the id is not set.
+    pub fn pump_id(&self, pump_id: Option<&str>) -> Result<&str, ServerError> {
+        Ok(&self.find_pump(pump_id)?.id)
+    }
+
+    pub fn check_pump(&self, pump_id: &str) -> Result<(), ServerError> {
+        self.find_pump(Some(pump_id))?;
+        Ok(())
+    }
+
+    pub fn check_sensor(&self, sensor_id: &str) -> Result<(), ServerError> {
+        self.find_sensor(Some(sensor_id))?;
+        Ok(())
+    }
+
+    fn find_pump(&self, id: Option<&str>) -> Result<&Pump, ServerError> {
+        Ok(find(&self.pumps, id, |p| &p.id).ok_or(LogicError::WaterPumpNotFound)?)
+    }
+
+    fn find_sensor(&self, id: Option<&str>) -> Result<&Sensor, ServerError> {
+        Ok(find(&self.sensors, id, |s| &s.id).ok_or(LogicError::WaterSensorNotFound)?)
+    }
+}
+
+/// Entry without id is found only if it is the only one, so a request without id does not run a random pump.
+fn find<'a, T, F: Fn(&T) -> &str>(entries: &'a [T], id: Option<&str>, entry_id: F) -> Option<&'a T> {
+    match id {
+        Some(id) => entries.iter().find(|e| entry_id(e).eq_ignore_ascii_case(id)),
+        None if entries.len() == 1 => entries.first(),
+        None => None
+    }
+}
+
+/// Ids are compared ignoring case, as they are found.
+fn check_unique<'a, I: Iterator<Item = &'a str>>(ids: I) -> Result<(), ServerError> {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id.to_ascii_lowercase()) {
+            return Err(LogicError::DuplicateWaterId.into());
+        }
+    }
+
+    Ok(())
+}
+
+impl Actuator for Water {
+    fn safe_state(&self) {
+        for pump in &self.pumps {
+            pump.pump.disable();
+        }
+    }
+
+    fn enforce_max_runtime(&self) {
+        for pump in &self.pumps {
+            if let Some(running) = pump.pump.running_for() {
+                if running > pump.max_runtime {
+                    error!("water pump {} runs for {}s, forcing it off", &pump.id, running.as_secs_f32());
+                    pump.pump.disable();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_without_id_only_single_entry() {
+        let single = ["default"];
+        let several = ["balcony", "kitchen"];
+
+        assert_eq!(find(&single, None, |e| e), Some(&"default"));
+        assert_eq!(find(&several, None, |e| e), None);
+        assert_eq!(find(&several, Some("Kitchen"), |e| e), Some(&"kitchen"));
+        assert_eq!(find(&several, Some("garden"), |e| e), None);
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        assert!(check_unique(["balcony", "kitchen"].iter().copied()).is_ok());
+        assert!(matches!(check_unique(["balcony", "Balcony"].iter().copied()),
+            Err(ServerError::Logic(LogicError::DuplicateWaterId))));
+    }
+}
\ No newline at end of file
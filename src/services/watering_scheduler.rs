@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::config::WateringSchedulerConfig;
+use crate::services::plants::Plants;
+use crate::services::shutdown::Shutdown;
+
+pub struct WateringScheduler;
+
+impl WateringScheduler {
+    pub fn start(config: &WateringSchedulerConfig, plants: &Arc<Plants>, shutdown: &Shutdown) -> JoinHandle<()> {
+        let period = Duration::from_secs(config.check_interval_seconds.max(1));
+        let plants = plants.clone();
+        let shutdown = shutdown.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.wait() => break
+                }
+
+                let plants = plants.clone();
+                let result = tokio::task::spawn_blocking(move || plants.auto_water()).await;
+                match result {
+                    Ok(Err(e)) => error!("error on scheduled watering: {}", e),
+                    Err(e) => error!("scheduled watering task failed: {}", e),
+                    Ok(Ok(())) => {}
+                }
+            }
+        })
+    }
+}
\ No newline at end of file
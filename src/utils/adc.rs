@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use rppal::i2c::I2c;
+#[cfg(target_os = "linux")]
+use rppal::spi::{Bus, Mode, SlaveSelect, Spi};
+use thiserror::Error;
+
+use crate::utils::rppal_error::RppalError;
+
+#[cfg(target_os = "linux")]
+const MCP3008_CLOCK_SPEED : u32 = 1_350_000;
+const MCP3008_CHANNELS : u8 = 8;
+
+#[cfg(target_os = "linux")]
+const ADS1115_CONFIG_REGISTER : u8 = 0x01;
+#[cfg(target_os = "linux")]
+const ADS1115_CONVERSION_REGISTER : u8 = 0x00;
+// Single shot conversion, ±4.096V range, 128 samples per second, comparator disabled.
+#[cfg(target_os = "linux")]
+const ADS1115_CONFIG : u16 = 0x8000 | 0x0200 | 0x0100 | 0x0080 | 0x0003;
+const ADS1115_CHANNELS : u8 = 4;
+
+/// Analog to digital converter, returns raw values of the channels.
+pub trait Adc : Send + Sync {
+    fn read(&self, channel: u8) -> Result<u16, AdcError>;
+}
+
+pub struct Mcp3008 {
+    #[cfg(target_os = "linux")]
+    spi: Mutex<Spi>
+}
+
+impl Mcp3008 {
+    #[cfg(target_os = "windows")]
+    pub fn new() -> Result<Self, AdcError> {
+        Ok(Mcp3008 {
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn new() -> Result<Self, AdcError> {
+        let spi = Spi::new(Bus::Spi0, SlaveSelect::Ss0, MCP3008_CLOCK_SPEED, Mode::Mode0)
+            .map_err(RppalError::from)?;
+
+        Ok(Mcp3008 {
+            spi: Mutex::new(spi)
+        })
+    }
+}
+
+impl Adc for Mcp3008 {
+    #[cfg(target_os = "windows")]
+    fn read(&self, channel: u8) -> Result<u16, AdcError> {
+        check_channel(channel, MCP3008_CHANNELS)?;
+        Ok(0)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read(&self, channel: u8) -> Result<u16, AdcError> {
+        check_channel(channel, MCP3008_CHANNELS)?;
+
+        let write = [1u8, (8 + channel) << 4, 0];
+        let mut read = [0u8; 3];
+
+        let spi = self.spi.lock().map_err(|_| AdcError::Poison)?;
+        spi.transfer(&mut read, &write).map_err(RppalError::from)?;
+
+        Ok((((read[1] & 3) as u16) << 8) | read[2] as u16)
+    }
+}
+
+pub struct Ads1115 {
+    #[cfg(target_os = "linux")]
+    i2c: Mutex<I2c>
+}
+
+impl Ads1115 {
+    #[cfg(target_os = "windows")]
+    pub fn new(_: u16) -> Result<Self, AdcError> {
+        Ok(Ads1115 {
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn new(address: u16) -> Result<Self, AdcError> {
+        let mut i2c = I2c::new().map_err(RppalError::from)?;
+        i2c.set_slave_address(address).map_err(RppalError::from)?;
+
+        Ok(Ads1115 {
+            i2c: Mutex::new(i2c)
+        })
+    }
+}
+
+impl Adc for Ads1115 {
+    #[cfg(target_os = "windows")]
+    fn read(&self, channel: u8) -> Result<u16, AdcError> {
+        check_channel(channel, ADS1115_CHANNELS)?;
+        Ok(0)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read(&self, channel: u8) -> Result<u16, AdcError> {
+        check_channel(channel, ADS1115_CHANNELS)?;
+
+        // Single ended input: AINx compared to GND.
+        let config = ADS1115_CONFIG | ((0x4 | channel as u16) << 12);
+
+        let i2c = self.i2c.lock().map_err(|_| AdcError::Poison)?;
+        i2c.block_write(ADS1115_CONFIG_REGISTER, &config.to_be_bytes()).map_err(RppalError::from)?;
+
+        thread::sleep(Duration::from_millis(10));
+
+        let mut buf = [0u8; 2];
+        i2c.block_read(ADS1115_CONVERSION_REGISTER, &mut buf).map_err(RppalError::from)?;
+
+        Ok(i16::from_be_bytes(buf).max(0) as u16)
+    }
+}
+
+/// Adc without hardware, returns values from the config.
+pub struct SimulatedAdc {
+    values: HashMap<u8, u16>
+}
+
+impl SimulatedAdc {
+    pub fn new(values: HashMap<u8, u16>) -> Self {
+        SimulatedAdc {
+            values
+        }
+    }
+}
+
+impl Adc for SimulatedAdc {
+    fn read(&self, channel: u8) -> Result<u16, AdcError> {
+        self.values
+            .get(&channel)
+            .copied()
+            .ok_or(AdcError::InvalidChannel(channel))
+    }
+}
+
+fn check_channel(channel: u8, count: u8) -> Result<(), AdcError> {
+    if channel >= count {
+        return Err(AdcError::InvalidChannel(channel));
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum AdcError {
+    #[error("Rppal error: {0}")]
+    Rppal(#[from] RppalError),
+    #[error("Invalid adc channel: {0}")]
+    InvalidChannel(u8),
+    #[error("Adc mutex is poison")]
+    Poison
+}
\ No newline at end of file
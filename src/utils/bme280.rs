@@ -0,0 +1,178 @@
+#[cfg(target_os = "linux")]
+use rppal::i2c::I2c;
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+
+use crate::utils::climate_sensor::{ClimateReading, ClimateSensor, ClimateSensorError};
+#[cfg(target_os = "linux")]
+use crate::utils::rppal_error::RppalError;
+
+#[cfg(target_os = "linux")]
+const CALIBRATION_T_P_REGISTER : u8 = 0x88;
+#[cfg(target_os = "linux")]
+const CALIBRATION_H1_REGISTER : u8 = 0xa1;
+#[cfg(target_os = "linux")]
+const CALIBRATION_H2_REGISTER : u8 = 0xe1;
+#[cfg(target_os = "linux")]
+const CTRL_HUM_REGISTER : u8 = 0xf2;
+#[cfg(target_os = "linux")]
+const CTRL_MEAS_REGISTER : u8 = 0xf4;
+#[cfg(target_os = "linux")]
+const DATA_REGISTER : u8 = 0xf7;
+// Temperature and pressure oversampling x1, forced mode.
+#[cfg(target_os = "linux")]
+const CTRL_MEAS_FORCED : u8 = 0b0010_0101;
+// Humidity oversampling x1.
+#[cfg(target_os = "linux")]
+const CTRL_HUM_X1 : u8 = 0b0000_0001;
+
+/// Temperature, humidity and pressure sensor connected by I2C.
+pub struct Bme280 {
+    #[cfg(target_os = "linux")]
+    i2c: Mutex<I2c>,
+    #[cfg(target_os = "linux")]
+    calibration: Calibration
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct Calibration {
+    t1: u16,
+    t2: i16,
+    t3: i16,
+    h1: u8,
+    h2: i16,
+    h3: u8,
+    h4: i16,
+    h5: i16,
+    h6: i8
+}
+
+impl Bme280 {
+    #[cfg(target_os = "windows")]
+    pub fn new(_: u16) -> Result<Self, ClimateSensorError> {
+        Ok(Bme280 {
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn new(address: u16) -> Result<Self, ClimateSensorError> {
+        let mut i2c = I2c::new().map_err(RppalError::from)?;
+        i2c.set_slave_address(address).map_err(RppalError::from)?;
+
+        let mut tp = [0u8; 24];
+        i2c.block_read(CALIBRATION_T_P_REGISTER, &mut tp).map_err(RppalError::from)?;
+        let mut h1 = [0u8; 1];
+        i2c.block_read(CALIBRATION_H1_REGISTER, &mut h1).map_err(RppalError::from)?;
+        let mut h = [0u8; 7];
+        i2c.block_read(CALIBRATION_H2_REGISTER, &mut h).map_err(RppalError::from)?;
+
+        let calibration = Calibration {
+            t1: u16::from_le_bytes([tp[0], tp[1]]),
+            t2: i16::from_le_bytes([tp[2], tp[3]]),
+            t3: i16::from_le_bytes([tp[4], tp[5]]),
+            h1: h1[0],
+            h2: i16::from_le_bytes([h[0], h[1]]),
+            h3: h[2],
+            h4: ((h[3] as i8 as i16) << 4) | (h[4] & 0x0f) as i16,
+            h5: ((h[5] as i8 as i16) << 4) | (h[4] >> 4) as i16,
+            h6: h[6] as i8
+        };
+
+        Ok(Bme280 {
+            i2c: Mutex::new(i2c),
+            calibration
+        })
+    }
+}
+
+impl Calibration {
+    /// Compensation formulas from the BME280 datasheet, returns temperature and t_fine.
+    fn temperature(&self, adc_t: i32) -> (f32, i32) {
+        let var1 = (((adc_t >> 3) - ((self.t1 as i32) << 1)) * self.t2 as i32) >> 11;
+        let var2 = (((((adc_t >> 4) - self.t1 as i32) * ((adc_t >> 4) - self.t1 as i32)) >> 12) * self.t3 as i32) >> 14;
+        let t_fine = var1 + var2;
+        let temperature = ((t_fine * 5 + 128) >> 8) as f32 / 100.0;
+        (temperature, t_fine)
+    }
+
+    fn humidity(&self, adc_h: i32, t_fine: i32) -> f32 {
+        let mut v = t_fine - 76800;
+        v = ((((adc_h << 14) - ((self.h4 as i32) << 20) - (self.h5 as i32 * v)) + 16384) >> 15)
+            * (((((((v * self.h6 as i32) >> 10) * (((v * self.h3 as i32) >> 11) + 32768)) >> 10) + 2097152)
+            * self.h2 as i32 + 8192) >> 14);
+        v -= ((((v >> 15) * (v >> 15)) >> 7) * self.h1 as i32) >> 4;
+        v = v.clamp(0, 419430400);
+        (v >> 12) as f32 / 1024.0
+    }
+}
+
+impl ClimateSensor for Bme280 {
+    #[cfg(target_os = "windows")]
+    fn read(&self) -> Result<ClimateReading, ClimateSensorError> {
+        Err(ClimateSensorError::Unsupported)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read(&self) -> Result<ClimateReading, ClimateSensorError> {
+        let i2c = self.i2c.lock().map_err(|_| ClimateSensorError::Poison)?;
+
+        i2c.block_write(CTRL_HUM_REGISTER, &[CTRL_HUM_X1]).map_err(RppalError::from)?;
+        i2c.block_write(CTRL_MEAS_REGISTER, &[CTRL_MEAS_FORCED]).map_err(RppalError::from)?;
+        thread::sleep(Duration::from_millis(10));
+
+        let mut data = [0u8; 8];
+        i2c.block_read(DATA_REGISTER, &mut data).map_err(RppalError::from)?;
+
+        let adc_t = ((data[3] as i32) << 12) | ((data[4] as i32) << 4) | ((data[5] as i32) >> 4);
+        let adc_h = ((data[6] as i32) << 8) | data[7] as i32;
+
+        let (temperature, t_fine) = self.calibration.temperature(adc_t);
+        Ok(ClimateReading {
+            temperature,
+            humidity: Some(self.calibration.humidity(adc_h, t_fine))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Temperature trimming from the datasheet example, humidity trimming of a typical sensor.
+    const CALIBRATION : Calibration = Calibration {
+        t1: 27504,
+        t2: 26435,
+        t3: -1000,
+        h1: 75,
+        h2: 362,
+        h3: 0,
+        h4: 324,
+        h5: 50,
+        h6: 30
+    };
+
+    #[test]
+    fn compensates_temperature() {
+        // datasheet example: adc_T 519888 is 25.08°C with t_fine 128422
+        assert_eq!(CALIBRATION.temperature(519888), (25.08, 128422));
+    }
+
+    #[test]
+    fn compensates_humidity() {
+        // expected values are from the floating point formula of the datasheet
+        for (adc_h, expected) in [(30000, 51.083), (26000, 28.738)] {
+            let humidity = CALIBRATION.humidity(adc_h, 128422);
+            assert!((humidity - expected).abs() < 0.05, "{} for {}, expected {}", humidity, adc_h, expected);
+        }
+    }
+
+    #[test]
+    fn clamps_humidity() {
+        assert_eq!(CALIBRATION.humidity(0, 128422), 0.0);
+        assert_eq!(CALIBRATION.humidity(65535, 128422), 100.0);
+    }
+}
\ No newline at end of file
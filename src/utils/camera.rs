@@ -2,26 +2,37 @@
 use rascam::*;
 #[cfg(target_os = "linux")]
 use std::thread;
-#[cfg(target_os = "linux")]
 use std::time::Duration;
 
 use thiserror::Error;
 
-pub struct Camera {
+use crate::utils::jpeg;
+
+const SIMULATED_WIDTH : u32 = 640;
+const SIMULATED_HEIGHT : u32 = 480;
+
+pub enum Camera {
     #[cfg(target_os="linux")]
-    info: CameraInfo
+    Device(CameraInfo),
+    /// Generates synthetic frames, used on development machines and in tests.
+    Simulated
 }
 
-impl Camera {
-    #[cfg(not(target_os = "linux"))]
-    pub fn new() -> Result<Self, CameraError> {
-        Ok(Camera {
-        })
+/// Camera which is ready to capture, should be used on the thread where it was activated.
+pub enum ActiveCamera {
+    #[cfg(target_os="linux")]
+    Device(SimpleCamera),
+    Simulated {
+        frame: u32,
+        width: u32,
+        height: u32
     }
+}
 
-    #[cfg(not(target_os = "linux"))]
-    pub fn make_photo(&self) -> Result<Vec<u8>, CameraError> {
-        Ok(Vec::new())
+impl Camera {
+    #[cfg(target_os = "windows")]
+    pub fn new() -> Result<Self, CameraError> {
+        Ok(Camera::Simulated)
     }
 
     #[cfg(target_os = "linux")]
@@ -33,23 +44,57 @@ impl Camera {
         }
 
         let first = info.cameras.remove(0);
-        Ok(Camera {
-            info: first
-        })
+        Ok(Camera::Device(first))
     }
 
-    #[cfg(target_os = "linux")]
-    pub fn make_photo(&self) -> Result<Vec<u8>, CameraError> {
-        let mut camera = SimpleCamera::new(self.info.clone())?;
-        camera.activate()?;
+    /// Frames are captured with the given width and height, or with the maximal size of the sensor.
+    pub fn activate(&self, warm_up: Duration, size: Option<(u32, u32)>) -> Result<ActiveCamera, CameraError> {
+        match self {
+            #[cfg(target_os = "linux")]
+            Camera::Device(info) => {
+                let mut camera = SimpleCamera::new(info.clone())?;
+                if let Some((width, height)) = size {
+                    camera.configure(CameraSettings {
+                        width,
+                        height,
+                        ..CameraSettings::default()
+                    });
+                }
+                camera.activate()?;
 
-        let sleep_duration = Duration::from_millis(2000);
-        thread::sleep(sleep_duration);
+                info!("camera activated, warming up...");
+                thread::sleep(warm_up);
+
+                Ok(ActiveCamera::Device(camera))
+            },
+            Camera::Simulated => {
+                info!("simulated camera activated, warm up {}ms is skipped", warm_up.as_millis());
+                let (width, height) = size.unwrap_or((SIMULATED_WIDTH, SIMULATED_HEIGHT));
+                Ok(ActiveCamera::Simulated {
+                    frame: 0,
+                    width,
+                    height
+                })
+            }
+        }
+    }
+}
 
-        let image = camera.take_one()?;
+impl ActiveCamera {
+    pub fn make_photo(&mut self) -> Result<Vec<u8>, CameraError> {
+        match self {
+            #[cfg(target_os = "linux")]
+            ActiveCamera::Device(camera) => {
+                let image = camera.take_one()?;
 
-        info!("copying photo to own memory...");
-        Ok(Vec::from(image.as_slice()))
+                info!("copying photo to own memory...");
+                Ok(Vec::from(image.as_slice()))
+            },
+            ActiveCamera::Simulated { frame, width, height } => {
+                *frame = frame.wrapping_add(1);
+                Ok(jpeg::simulated_frame(*frame, *width, *height)?)
+            }
+        }
     }
 }
 
@@ -60,5 +105,9 @@ pub enum CameraError {
     Rascam(#[from] rascam::CameraError),
     #[cfg(target_os = "linux")]
     #[error("Camera not found")]
-    NotFound
-}
+    NotFound,
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("Camera service is stopped")]
+    Stopped
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+use std::io;
+
+use thiserror::Error;
+
+use crate::utils::rppal_error::RppalError;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClimateReading {
+    pub temperature: f32,
+    pub humidity: Option<f32>
+}
+
+/// Sensor connected directly to the raspberry pi.
+pub trait ClimateSensor : Send + Sync {
+    fn read(&self) -> Result<ClimateReading, ClimateSensorError>;
+}
+
+#[derive(Error, Debug)]
+pub enum ClimateSensorError {
+    #[error("Io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Rppal error: {0}")]
+    Rppal(#[from] RppalError),
+    #[error("Invalid sensor data: {0}")]
+    InvalidData(String),
+    #[error("Sensor mutex is poison")]
+    Poison,
+    #[cfg(target_os = "windows")]
+    #[error("Sensor is not supported on this platform")]
+    Unsupported
+}
\ No newline at end of file
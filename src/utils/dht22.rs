@@ -0,0 +1,148 @@
+#[cfg(target_os = "linux")]
+use rppal::gpio::{Gpio, IoPin, Level, Mode};
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::time::{Duration, Instant};
+
+use crate::utils::climate_sensor::{ClimateReading, ClimateSensor, ClimateSensorError};
+#[cfg(target_os = "linux")]
+use crate::utils::rppal_error::RppalError;
+
+#[cfg(target_os = "linux")]
+const START_SIGNAL : Duration = Duration::from_micros(1200);
+#[cfg(target_os = "linux")]
+const LEVEL_TIMEOUT : Duration = Duration::from_micros(200);
+#[cfg(target_os = "linux")]
+const ONE_BIT_THRESHOLD : Duration = Duration::from_micros(50);
+
+/// Temperature and humidity sensor with single wire protocol, timings are measured by polling the pin.
+pub struct Dht22 {
+    #[cfg(target_os = "linux")]
+    gpio: Gpio,
+    #[cfg(target_os = "linux")]
+    pin: u8,
+    #[cfg(target_os = "linux")]
+    lock: Mutex<()>
+}
+
+impl Dht22 {
+    #[cfg(target_os = "windows")]
+    pub fn new(_: u8) -> Result<Self, ClimateSensorError> {
+        Ok(Dht22 {
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn new(pin: u8) -> Result<Self, ClimateSensorError> {
+        let gpio = Gpio::new().map_err(RppalError::from)?;
+        Ok(Dht22 {
+            gpio,
+            pin,
+            lock: Mutex::new(())
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_bytes(&self) -> Result<[u8; 5], ClimateSensorError> {
+        let _guard = self.lock.lock().map_err(|_| ClimateSensorError::Poison)?;
+
+        let mut pin = self.gpio.get(self.pin)
+            .map_err(RppalError::from)?
+            .into_io(Mode::Output);
+
+        pin.set_low();
+        thread::sleep(START_SIGNAL);
+        pin.set_high();
+        pin.set_mode(Mode::Input);
+
+        // Sensor response: 80us low, 80us high.
+        Self::wait_for(&pin, Level::Low)?;
+        Self::wait_for(&pin, Level::High)?;
+        Self::wait_for(&pin, Level::Low)?;
+
+        let mut bytes = [0u8; 5];
+        for i in 0..40 {
+            // Every bit starts with 50us low, then high level length defines the value.
+            Self::wait_for(&pin, Level::High)?;
+            let high = Self::wait_for(&pin, Level::Low)?;
+
+            if high > ONE_BIT_THRESHOLD {
+                bytes[i / 8] |= 1 << (7 - i % 8);
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn wait_for(pin: &IoPin, level: Level) -> Result<Duration, ClimateSensorError> {
+        let start = Instant::now();
+        while pin.read() != level {
+            if start.elapsed() > LEVEL_TIMEOUT {
+                return Err(ClimateSensorError::InvalidData("sensor response timeout".to_owned()));
+            }
+        }
+
+        Ok(start.elapsed())
+    }
+
+    fn decode(bytes: [u8; 5]) -> Result<ClimateReading, ClimateSensorError> {
+        let sum = bytes[..4]
+            .iter()
+            .fold(0u8, |acc, b| acc.wrapping_add(*b));
+
+        if sum != bytes[4] {
+            return Err(ClimateSensorError::InvalidData("checksum mismatch".to_owned()));
+        }
+
+        let humidity = u16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 10.0;
+        let temperature = u16::from_be_bytes([bytes[2] & 0x7f, bytes[3]]) as f32 / 10.0;
+        let temperature = if bytes[2] & 0x80 != 0 { -temperature } else { temperature };
+
+        Ok(ClimateReading {
+            temperature,
+            humidity: Some(humidity)
+        })
+    }
+}
+
+impl ClimateSensor for Dht22 {
+    #[cfg(target_os = "windows")]
+    fn read(&self) -> Result<ClimateReading, ClimateSensorError> {
+        Err(ClimateSensorError::Unsupported)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read(&self) -> Result<ClimateReading, ClimateSensorError> {
+        Self::decode(self.read_bytes()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_datasheet_example() {
+        let reading = Dht22::decode([0x02, 0x8c, 0x01, 0x5f, 0xee]).unwrap();
+
+        assert_eq!(reading.temperature, 35.1);
+        assert_eq!(reading.humidity, Some(65.2));
+    }
+
+    #[test]
+    fn decodes_negative_temperature() {
+        let reading = Dht22::decode([0x01, 0xf4, 0x80, 0x65, 0xda]).unwrap();
+
+        assert_eq!(reading.temperature, -10.1);
+        assert_eq!(reading.humidity, Some(50.0));
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        assert!(matches!(Dht22::decode([0x02, 0x8c, 0x01, 0x5f, 0xef]), Err(ClimateSensorError::InvalidData(_))));
+    }
+}
\ No newline at end of file
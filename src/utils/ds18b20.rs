@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::climate_sensor::{ClimateReading, ClimateSensor, ClimateSensorError};
+
+/// 1-Wire temperature sensor, read through w1-therm sysfs interface.
+pub struct Ds18b20 {
+    path: PathBuf
+}
+
+impl Ds18b20 {
+    pub fn new<P: AsRef<Path>>(devices_path: P, device_id: &str) -> Self {
+        Ds18b20 {
+            path: devices_path.as_ref().join(device_id).join("w1_slave")
+        }
+    }
+
+    /// Parses w1_slave content:
+    /// `72 01 4b 46 7f ff 0e 10 57 : crc=57 YES`
+    /// `72 01 4b 46 7f ff 0e 10 57 t=23125`
+    fn parse(content: &str) -> Result<f32, ClimateSensorError> {
+        let mut lines = content.lines();
+
+        let crc_line = lines.next().unwrap_or("");
+        if !crc_line.trim_end().ends_with("YES") {
+            return Err(ClimateSensorError::InvalidData(format!("crc check failed: {}", crc_line)));
+        }
+
+        let temp_line = lines.next().unwrap_or("");
+        let value = temp_line
+            .split("t=")
+            .nth(1)
+            .ok_or_else(|| ClimateSensorError::InvalidData(format!("temperature not found: {}", temp_line)))?;
+
+        let millidegrees : i32 = value.trim()
+            .parse()
+            .map_err(|_| ClimateSensorError::InvalidData(format!("invalid temperature: {}", value)))?;
+
+        Ok(millidegrees as f32 / 1000.0)
+    }
+}
+
+impl ClimateSensor for Ds18b20 {
+    fn read(&self) -> Result<ClimateReading, ClimateSensorError> {
+        let content = fs::read_to_string(&self.path)?;
+        Ok(ClimateReading {
+            temperature: Self::parse(&content)?,
+            humidity: None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use super::*;
+
+    /// Fake w1 sysfs directory with one device, the content is not written if `None`.
+    fn device(name: &str, content: Option<&str>) -> Ds18b20 {
+        let devices_path = env::temp_dir().join(format!("rpi_home_w1_{}_{}", process::id(), name));
+        let device_path = devices_path.join("28-000005e2fdc3");
+        fs::create_dir_all(&device_path).unwrap();
+        if let Some(content) = content {
+            fs::write(device_path.join("w1_slave"), content).unwrap();
+        }
+
+        Ds18b20::new(&devices_path, "28-000005e2fdc3")
+    }
+
+    #[test]
+    fn reads_temperature() {
+        let sensor = device("good", Some("72 01 4b 46 7f ff 0e 10 57 : crc=57 YES\n72 01 4b 46 7f ff 0e 10 57 t=23125\n"));
+        let reading = sensor.read().unwrap();
+
+        assert_eq!(reading.temperature, 23.125);
+        assert_eq!(reading.humidity, None);
+    }
+
+    #[test]
+    fn reads_negative_temperature() {
+        let sensor = device("negative", Some("ec ff 4b 46 7f ff 0c 10 5a : crc=5a YES\nec ff 4b 46 7f ff 0c 10 5a t=-1250\n"));
+        assert_eq!(sensor.read().unwrap().temperature, -1.25);
+    }
+
+    #[test]
+    fn rejects_failed_crc() {
+        let sensor = device("crc", Some("72 01 4b 46 7f ff 0e 10 57 : crc=00 NO\n72 01 4b 46 7f ff 0e 10 57 t=23125\n"));
+        assert!(matches!(sensor.read(), Err(ClimateSensorError::InvalidData(_))));
+    }
+
+    #[test]
+    fn rejects_missing_temperature() {
+        let sensor = device("truncated", Some("72 01 4b 46 7f ff 0e 10 57 : crc=57 YES\n"));
+        assert!(matches!(sensor.read(), Err(ClimateSensorError::InvalidData(_))));
+    }
+
+    #[test]
+    fn fails_without_device_file() {
+        let sensor = device("missing", None);
+        assert!(matches!(sensor.read(), Err(ClimateSensorError::Io(_))));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,192 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, GrayImage, ImageError, RgbImage};
+use image::codecs::jpeg::JpegDecoder;
+use image::imageops::{self, FilterType};
+use serde::{Deserialize, Serialize};
+
+/// Photos are decoded with DCT scaling close to this size, it is enough for metrics and cheap on Pi Zero.
+const ANALYSIS_WIDTH : u16 = 320;
+const ANALYSIS_HEIGHT : u16 = 240;
+const FINGERPRINT_WIDTH : u32 = 64;
+const FINGERPRINT_HEIGHT : u32 = 48;
+/// Minimal `2g - r - b` value of the plant pixel.
+const EXCESS_GREEN_THRESHOLD : i32 = 20;
+
+/// Part of the image in fractions of its size.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Region {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ImageMetrics {
+    /// Share of green pixels in the region of interest, 0 - 1.
+    pub green_ratio: f32,
+    /// Mean luminance of the whole image, 0 - 1.
+    pub brightness: f32,
+    /// Mean difference with the previous frame, 0 - 1.
+    pub change: Option<f32>
+}
+
+pub struct Analysis {
+    pub metrics: ImageMetrics,
+    /// Small grayscale copy of the image which should be passed with the next frame.
+    pub fingerprint: GrayImage
+}
+
+impl Default for Region {
+    fn default() -> Self {
+        Region {
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0
+        }
+    }
+}
+
+pub fn analyze(data: &[u8], region: &Region, previous: Option<&GrayImage>) -> Result<Analysis, ImageError> {
+    let mut decoder = JpegDecoder::new(Cursor::new(data))?;
+    decoder.scale(ANALYSIS_WIDTH, ANALYSIS_HEIGHT)?;
+    let image = DynamicImage::from_decoder(decoder)?;
+
+    let green_ratio = green_ratio(&image.to_rgb8(), region);
+
+    let gray = image.to_luma8();
+    let brightness = mean(gray.as_raw().iter().map(|p| *p as u64)) / 255.0;
+
+    let fingerprint = imageops::resize(&gray, FINGERPRINT_WIDTH, FINGERPRINT_HEIGHT, FilterType::Triangle);
+    let change = previous.map(|p| {
+        mean(p.as_raw().iter().zip(fingerprint.as_raw()).map(|(a, b)| a.abs_diff(*b) as u64)) / 255.0
+    });
+
+    Ok(Analysis {
+        metrics: ImageMetrics {
+            green_ratio,
+            brightness,
+            change
+        },
+        fingerprint
+    })
+}
+
+fn green_ratio(image: &RgbImage, region: &Region) -> f32 {
+    let (width, height) = image.dimensions();
+    let x0 = ((region.x.clamp(0.0, 1.0) * width as f32) as u32).min(width);
+    let y0 = ((region.y.clamp(0.0, 1.0) * height as f32) as u32).min(height);
+    let x1 = (((region.x + region.width).clamp(0.0, 1.0) * width as f32) as u32).clamp(x0, width);
+    let y1 = (((region.y + region.height).clamp(0.0, 1.0) * height as f32) as u32).clamp(y0, height);
+
+    let mut green = 0u64;
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let [r, g, b] = image.get_pixel(x, y).0;
+            if 2 * g as i32 - r as i32 - b as i32 > EXCESS_GREEN_THRESHOLD {
+                green += 1;
+            }
+        }
+    }
+
+    let total = (x1 - x0) as u64 * (y1 - y0) as u64;
+    if total == 0 {
+        return 0.0;
+    }
+
+    green as f32 / total as f32
+}
+
+fn mean<I: ExactSizeIterator<Item = u64>>(values: I) -> f32 {
+    let count = values.len();
+    if count == 0 {
+        return 0.0;
+    }
+
+    values.sum::<u64>() as f32 / count as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ColorType, Rgb};
+    use image::codecs::jpeg::JpegEncoder;
+
+    use super::*;
+
+    const GREEN : Rgb<u8> = Rgb([40, 200, 40]);
+    const GRAY : Rgb<u8> = Rgb([128, 128, 128]);
+
+    fn jpeg(width: u32, height: u32, pixel: impl Fn(u32, u32) -> Rgb<u8>) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, pixel);
+        let mut result = Vec::new();
+        JpegEncoder::new_with_quality(&mut result, 95)
+            .encode(image.as_raw(), width, height, ColorType::Rgb8)
+            .unwrap();
+        result
+    }
+
+    /// Plant on the left half, gray wall on the right half.
+    fn half_green() -> Vec<u8> {
+        jpeg(320, 240, |x, _| if x < 160 { GREEN } else { GRAY })
+    }
+
+    fn assert_near(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 0.03, "{}, expected {}", value, expected);
+    }
+
+    #[test]
+    fn measures_green_ratio_of_whole_image() {
+        let analysis = analyze(&half_green(), &Region::default(), None).unwrap();
+        assert_near(analysis.metrics.green_ratio, 0.5);
+    }
+
+    #[test]
+    fn measures_green_ratio_inside_region() {
+        let plant = Region { x: 0.0, y: 0.0, width: 0.4, height: 1.0 };
+        let wall = Region { x: 0.6, y: 0.0, width: 0.4, height: 1.0 };
+
+        assert_near(analyze(&half_green(), &plant, None).unwrap().metrics.green_ratio, 1.0);
+        assert_near(analyze(&half_green(), &wall, None).unwrap().metrics.green_ratio, 0.0);
+    }
+
+    #[test]
+    fn clamps_region_outside_image() {
+        let outside = Region { x: 1.5, y: 0.0, width: 1.0, height: 1.0 };
+        assert_eq!(analyze(&half_green(), &outside, None).unwrap().metrics.green_ratio, 0.0);
+    }
+
+    #[test]
+    fn measures_brightness() {
+        let black = jpeg(320, 240, |_, _| Rgb([0, 0, 0]));
+        let white = jpeg(320, 240, |_, _| Rgb([255, 255, 255]));
+        let gray = jpeg(320, 240, |_, _| GRAY);
+
+        assert_near(analyze(&black, &Region::default(), None).unwrap().metrics.brightness, 0.0);
+        assert_near(analyze(&white, &Region::default(), None).unwrap().metrics.brightness, 1.0);
+        assert_near(analyze(&gray, &Region::default(), None).unwrap().metrics.brightness, 0.5);
+    }
+
+    #[test]
+    fn measures_change_with_previous_frame() {
+        let first = analyze(&half_green(), &Region::default(), None).unwrap();
+        assert!(first.metrics.change.is_none());
+
+        let same = analyze(&half_green(), &Region::default(), Some(&first.fingerprint)).unwrap();
+        assert_near(same.metrics.change.unwrap(), 0.0);
+
+        let white = jpeg(320, 240, |_, _| Rgb([255, 255, 255]));
+        let changed = analyze(&white, &Region::default(), Some(&first.fingerprint)).unwrap();
+        assert!(changed.metrics.change.unwrap() > 0.3);
+    }
+
+    #[test]
+    fn decodes_large_photo_at_analysis_size() {
+        let photo = jpeg(1280, 960, |x, _| if x < 640 { GREEN } else { GRAY });
+        let analysis = analyze(&photo, &Region::default(), None).unwrap();
+
+        assert_eq!(analysis.fingerprint.dimensions(), (FINGERPRINT_WIDTH, FINGERPRINT_HEIGHT));
+        assert_near(analysis.metrics.green_ratio, 0.5);
+    }
+}
\ No newline at end of file
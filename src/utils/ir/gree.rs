@@ -0,0 +1,117 @@
+use crate::utils::ir::{BitTimings, IrCommand, IrEncoder, IrMode};
+
+const HEADER_MARK : u32 = 9000;
+const HEADER_SPACE : u32 = 4500;
+const MESSAGE_SPACE : u32 = 19980;
+const BIT : BitTimings = BitTimings {
+    mark: 620,
+    one_space: 1600,
+    zero_space: 540
+};
+
+const MIN_TEMPERATURE : i32 = 16;
+const MAX_TEMPERATURE : i32 = 30;
+// Light on.
+const BYTE_2 : u8 = 0x20;
+const BYTE_3 : u8 = 0x50;
+// Footer between two blocks: 0b010 sent LSB first.
+const BLOCK_FOOTER : u8 = 0b010;
+
+/// Gree YB0F2 compatible remote: 8 bytes sent LSB first in two blocks of 4 bytes.
+pub struct Gree;
+
+impl Gree {
+    fn bytes(command: &IrCommand) -> [u8; 8] {
+        let mode = match command.mode {
+            IrMode::Auto => 0,
+            IrMode::Cool => 1,
+            IrMode::Dry => 2,
+            IrMode::Fan => 3,
+            IrMode::Heat => 4
+        };
+        let power = if command.power { 0x08 } else { 0 };
+        let temperature = (command.temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE) - MIN_TEMPERATURE) as u8;
+
+        let mut bytes = [mode | power, temperature, BYTE_2, BYTE_3, 0, 0, 0, 0];
+        bytes[7] = Self::checksum(&bytes) << 4;
+        bytes
+    }
+
+    fn checksum(bytes: &[u8; 8]) -> u8 {
+        let low : u32 = bytes[0..4].iter().map(|b| (b & 0x0f) as u32).sum();
+        let high : u32 = bytes[4..7].iter().map(|b| (b >> 4) as u32).sum();
+        ((10 + low + high) & 0x0f) as u8
+    }
+}
+
+impl IrEncoder for Gree {
+    fn encode(&self, command: &IrCommand) -> Vec<u32> {
+        let bytes = Self::bytes(command);
+        let mut pulses = vec![HEADER_MARK, HEADER_SPACE];
+
+        for b in &bytes[0..4] {
+            BIT.push_lsb_first(&mut pulses, *b, 8);
+        }
+
+        BIT.push_lsb_first(&mut pulses, BLOCK_FOOTER, 3);
+        pulses.push(BIT.mark);
+        pulses.push(MESSAGE_SPACE);
+
+        for b in &bytes[4..8] {
+            BIT.push_lsb_first(&mut pulses, *b, 8);
+        }
+
+        pulses.push(BIT.mark);
+        pulses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ir::fixtures::assert_timings;
+
+    // bytes 09 08 20 50 00 00 00 b0, widths are shifted like IR receiver output
+    const GREE_COOL_24_ON : [u32; 139] = [
+        9040, 4430, 675, 1515, 650, 480, 660, 470, 675, 1515, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 1530, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 1515, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 1540,
+        660, 470, 675, 1515, 650, 480, 660, 470, 675, 1515, 650, 480,
+        660, 19910, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 1540,
+        660, 1530, 675, 455, 650, 1540, 660
+    ];
+
+    // bytes 04 0e 20 50 00 00 00 c0
+    const GREE_HEAT_30_OFF : [u32; 139] = [
+        9040, 4430, 675, 455, 650, 480, 660, 1530, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 1515, 650, 1540,
+        660, 1530, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 1515, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 1540,
+        660, 470, 675, 1515, 650, 480, 660, 470, 675, 1515, 650, 480,
+        660, 19910, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 455, 650, 480, 660, 470, 675, 455, 650, 480,
+        660, 470, 675, 1515, 650, 1540, 660
+    ];
+
+    #[test]
+    fn encodes_cool_on() {
+        let command = IrCommand { power: true, mode: IrMode::Cool, temperature: 24 };
+        assert_timings(&Gree.encode(&command), &GREE_COOL_24_ON);
+    }
+
+    #[test]
+    fn encodes_heat_off_with_clamped_temperature() {
+        let command = IrCommand { power: false, mode: IrMode::Heat, temperature: 32 };
+        assert_timings(&Gree.encode(&command), &GREE_HEAT_30_OFF);
+    }
+}
\ No newline at end of file
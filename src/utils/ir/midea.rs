@@ -0,0 +1,132 @@
+use crate::utils::ir::{BitTimings, IrCommand, IrEncoder, IrMode};
+
+const HEADER_MARK : u32 = 4480;
+const HEADER_SPACE : u32 = 4480;
+const MESSAGE_SPACE : u32 = 5600;
+const BIT : BitTimings = BitTimings {
+    mark: 560,
+    one_space: 1680,
+    zero_space: 560
+};
+
+const MIN_TEMPERATURE : i32 = 17;
+const MAX_TEMPERATURE : i32 = 30;
+const COMMAND_HEADER : u8 = 0xa1;
+const POWER : u8 = 0x80;
+// Automatic fan speed.
+const FAN_AUTO : u8 = 0;
+const TIMER_OFF : u8 = 0xff;
+
+/// Midea compatible remote: 6 bytes sent MSB first, the frame is repeated with all bits inverted.
+pub struct Midea;
+
+impl Midea {
+    fn bytes(command: &IrCommand) -> [u8; 6] {
+        let mode = match command.mode {
+            IrMode::Cool => 0,
+            IrMode::Dry => 1,
+            IrMode::Auto => 2,
+            IrMode::Heat => 3,
+            IrMode::Fan => 4
+        };
+        let power = if command.power { POWER } else { 0 };
+        let temperature = (command.temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE) - MIN_TEMPERATURE) as u8;
+
+        let mut bytes = [COMMAND_HEADER, power | FAN_AUTO | mode, temperature, TIMER_OFF, TIMER_OFF, 0];
+        bytes[5] = Self::checksum(&bytes);
+        bytes
+    }
+
+    /// Sum of bit reversed bytes, result is negated and reversed back.
+    fn checksum(bytes: &[u8; 6]) -> u8 {
+        let sum = bytes[0..5]
+            .iter()
+            .fold(0u8, |acc, b| acc.wrapping_add(b.reverse_bits()));
+
+        0u8.wrapping_sub(sum).reverse_bits()
+    }
+
+    fn push_frame(pulses: &mut Vec<u32>, bytes: &[u8; 6], inverted: bool) {
+        pulses.push(HEADER_MARK);
+        pulses.push(HEADER_SPACE);
+
+        for b in bytes {
+            BIT.push_msb_first(pulses, if inverted { !b } else { *b });
+        }
+
+        pulses.push(BIT.mark);
+    }
+}
+
+impl IrEncoder for Midea {
+    fn encode(&self, command: &IrCommand) -> Vec<u32> {
+        let bytes = Self::bytes(command);
+        let mut pulses = Vec::new();
+
+        Self::push_frame(&mut pulses, &bytes, false);
+        pulses.push(MESSAGE_SPACE);
+        Self::push_frame(&mut pulses, &bytes, true);
+
+        pulses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ir::fixtures::assert_timings;
+
+    // bytes a1 80 07 ff ff 39 and the inverted frame, widths are shifted like IR receiver output
+    const MIDEA_COOL_24_ON : [u32; 199] = [
+        4520, 4410, 615, 1595, 590, 500, 600, 1610, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 1620, 600, 1610, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 1595, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 500,
+        600, 490, 615, 1595, 590, 1620, 600, 1610, 615, 475, 590, 500,
+        600, 1610, 615, 5515, 4510, 4420, 600, 490, 615, 1595, 590, 500,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 475, 590, 500,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 1595, 590, 1620, 600, 490, 615, 475, 590, 500,
+        600, 1610, 615, 1595, 590, 500, 600
+    ];
+
+    // bytes a1 03 03 ff ff bf and the inverted frame
+    const MIDEA_HEAT_20_OFF : [u32; 199] = [
+        4520, 4410, 615, 1595, 590, 500, 600, 1610, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 1620, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 1610, 615, 1595, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 475, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 490, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 1610, 615, 5515, 4510, 4420, 600, 490, 615, 1595, 590, 500,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 475, 590, 1620,
+        600, 1610, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 500,
+        600, 490, 615, 1595, 590, 1620, 600, 1610, 615, 1595, 590, 1620,
+        600, 1610, 615, 475, 590, 500, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 1620, 600, 490, 615, 475, 590, 500,
+        600, 490, 615, 475, 590, 500, 600
+    ];
+
+    #[test]
+    fn encodes_cool_on() {
+        let command = IrCommand { power: true, mode: IrMode::Cool, temperature: 24 };
+        assert_timings(&Midea.encode(&command), &MIDEA_COOL_24_ON);
+    }
+
+    #[test]
+    fn encodes_heat_off() {
+        let command = IrCommand { power: false, mode: IrMode::Heat, temperature: 20 };
+        assert_timings(&Midea.encode(&command), &MIDEA_HEAT_20_OFF);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+pub mod gree;
+pub mod midea;
+pub mod transmitter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IrMode {
+    Auto,
+    Cool,
+    Dry,
+    Fan,
+    Heat
+}
+
+/// State of the conditioner which is sent by one IR frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IrCommand {
+    pub power: bool,
+    pub mode: IrMode,
+    pub temperature: i32
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IrProtocol {
+    Gree,
+    Midea
+}
+
+/// Converts command to pulse train: durations in microseconds,
+/// even items are marks (carrier on), odd items are spaces.
+pub trait IrEncoder {
+    fn encode(&self, command: &IrCommand) -> Vec<u32>;
+}
+
+impl IrProtocol {
+    pub fn encode(&self, command: &IrCommand) -> Vec<u32> {
+        match self {
+            IrProtocol::Gree => gree::Gree.encode(command),
+            IrProtocol::Midea => midea::Midea.encode(command)
+        }
+    }
+}
+
+pub struct BitTimings {
+    pub mark: u32,
+    pub one_space: u32,
+    pub zero_space: u32
+}
+
+impl BitTimings {
+    pub fn push_lsb_first(&self, pulses: &mut Vec<u32>, value: u8, bits: u8) {
+        for i in 0..bits {
+            self.push_bit(pulses, value & (1 << i) != 0);
+        }
+    }
+
+    pub fn push_msb_first(&self, pulses: &mut Vec<u32>, value: u8) {
+        for i in (0..8).rev() {
+            self.push_bit(pulses, value & (1 << i) != 0);
+        }
+    }
+
+    fn push_bit(&self, pulses: &mut Vec<u32>, bit: bool) {
+        pulses.push(self.mark);
+        pulses.push(if bit { self.one_space } else { self.zero_space });
+    }
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    /// Receivers stretch marks and shorten spaces, so widths are compared with tolerance.
+    const TOLERANCE_PERCENT : u32 = 25;
+
+    pub fn assert_timings(actual: &[u32], expected: &[u32]) {
+        assert_eq!(actual.len(), expected.len(), "pulse count");
+        for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+            let diff = a.abs_diff(*e);
+            assert!(diff * 100 <= e * TOLERANCE_PERCENT, "pulse {}: {}us, expected {}us", i, a, e);
+        }
+    }
+}
\ No newline at end of file
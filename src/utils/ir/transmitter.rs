@@ -0,0 +1,77 @@
+#[cfg(target_os = "linux")]
+use rppal::gpio::{Gpio, OutputPin};
+#[cfg(target_os = "linux")]
+use std::time::{Duration, Instant};
+
+use crate::utils::rppal_error::RppalError;
+
+/// IR LED connected to the GPIO pin, carrier is generated by toggling the pin.
+pub struct IrTransmitter {
+    #[cfg(target_os = "linux")]
+    pin: OutputPin,
+    #[cfg(target_os = "linux")]
+    half_period: Duration
+}
+
+impl IrTransmitter {
+    #[cfg(target_os = "windows")]
+    pub fn new(_: u8, _: u32) -> Result<Self, RppalError> {
+        Ok(IrTransmitter {
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn new(pin: u8, carrier_hz: u32) -> Result<Self, RppalError> {
+        let mut pin = Gpio::new()?
+            .get(pin)?
+            .into_output();
+
+        pin.set_low();
+
+        Ok(IrTransmitter {
+            pin,
+            half_period: Duration::from_nanos(500_000_000 / carrier_hz.max(1) as u64)
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn send(&mut self, _: &[u32]) -> Result<(), RppalError> {
+        Ok(())
+    }
+
+    /// Sends pulse train, timings are kept by busy waiting so the call blocks for the whole frame.
+    #[cfg(target_os = "linux")]
+    pub fn send(&mut self, pulses: &[u32]) -> Result<(), RppalError> {
+        for (i, duration) in pulses.iter().enumerate() {
+            let duration = Duration::from_micros(*duration as u64);
+            if i % 2 == 0 {
+                self.mark(duration);
+            } else {
+                self.space(duration);
+            }
+        }
+
+        self.pin.set_low();
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn mark(&mut self, duration: Duration) {
+        let start = Instant::now();
+        let mut next_toggle = self.half_period;
+        while start.elapsed() < duration {
+            if start.elapsed() >= next_toggle {
+                self.pin.toggle();
+                next_toggle += self.half_period;
+            }
+        }
+        self.pin.set_low();
+    }
+
+    #[cfg(target_os = "linux")]
+    fn space(&mut self, duration: Duration) {
+        self.pin.set_low();
+        let start = Instant::now();
+        while start.elapsed() < duration {}
+    }
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use image::{ColorType, ImageError, ImageFormat, Rgb, RgbImage};
+use image::codecs::jpeg::JpegEncoder;
+
+/// Scales the photo down to fit into the given size keeping aspect ratio and re-encodes it
+/// with the given quality, the photo is never scaled up.
+pub fn resize(data: &[u8], width: Option<u32>, height: Option<u32>, quality: u8) -> Result<Vec<u8>, ImageError> {
+    let mut image = image::load_from_memory_with_format(data, ImageFormat::Jpeg)?;
+
+    let width = width.unwrap_or(image.width()).min(image.width());
+    let height = height.unwrap_or(image.height()).min(image.height());
+    if width != image.width() || height != image.height() {
+        image = image.thumbnail(width.max(1), height.max(1));
+    }
+
+    let rgb = image.to_rgb8();
+    let mut result = Vec::new();
+    JpegEncoder::new_with_quality(&mut result, quality.clamp(1, 100))
+        .encode(rgb.as_raw(), rgb.width(), rgb.height(), ColorType::Rgb8)?;
+
+    Ok(result)
+}
+
+/// Green gradient with a bar moving across it, lets to see that stream frames change.
+pub fn simulated_frame(index: u32, width: u32, height: u32) -> Result<Vec<u8>, ImageError> {
+    let bar = index.wrapping_mul(8) % width.max(1);
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        if x.abs_diff(bar) < 8 {
+            Rgb([230, 230, 230])
+        } else {
+            Rgb([40, (80 + y * 150 / height.max(1)) as u8, 40])
+        }
+    });
+
+    let mut result = Vec::new();
+    JpegEncoder::new(&mut result)
+        .encode(image.as_raw(), width, height, ColorType::Rgb8)?;
+
+    Ok(result)
+}
\ No newline at end of file
@@ -2,4 +2,18 @@ pub mod camera;
 pub mod water_sensor;
 pub mod rppal_error;
 pub mod water_pump;
-pub mod servo;
\ No newline at end of file
+pub mod servo;
+pub mod time;
+pub mod storage;
+pub mod adc;
+pub mod climate_sensor;
+pub mod ds18b20;
+pub mod dht22;
+pub mod bme280;
+pub mod rf433;
+pub mod ir;
+pub mod jpeg;
+pub mod image_metrics;
+pub mod watchdog;
+pub mod systemd;
+pub mod prometheus;
\ No newline at end of file
@@ -0,0 +1,74 @@
+use std::fmt::Write;
+
+/// Builder of the Prometheus text exposition format.
+pub struct Exposition {
+    text: String
+}
+
+impl Exposition {
+    pub fn new() -> Self {
+        Exposition {
+            text: String::new()
+        }
+    }
+
+    /// Starts a metric family, `kind` is `counter`, `gauge` or `histogram`.
+    pub fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", name, kind);
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.text.push_str(name);
+
+        if !labels.is_empty() {
+            self.text.push('{');
+            for (i, (label, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.text.push(',');
+                }
+                let _ = write!(self.text, "{}=\"{}\"", label, escape(value));
+            }
+            self.text.push('}');
+        }
+
+        let _ = writeln!(self.text, " {}", value);
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+impl Default for Exposition {
+    fn default() -> Self {
+        Exposition::new()
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_escaped_samples() {
+        let mut out = Exposition::new();
+        out.family("rpi_home_switch", "gauge", "Switch state.");
+        out.sample("rpi_home_switch", &[("name", "hall \"main\"\\lamp\n"), ("room", "hall")], 1.0);
+        out.sample("rpi_home_uptime_seconds", &[], 12.5);
+
+        assert_eq!(out.into_string(), "\
+# HELP rpi_home_switch Switch state.
+# TYPE rpi_home_switch gauge
+rpi_home_switch{name=\"hall \\\"main\\\"\\\\lamp\\n\",room=\"hall\"} 1
+rpi_home_uptime_seconds 12.5
+");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+pub mod nexus;
+pub mod prologue;
+pub mod receiver;
+
+/// Part of the signal with constant level.
+#[derive(Debug, Clone, Copy)]
+pub struct Pulse {
+    pub high: bool,
+    pub duration_us: u32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherReading {
+    pub id: u8,
+    pub channel: i32,
+    pub temperature: f32,
+    /// Not set for sensors without humidity element.
+    pub humidity: Option<i32>,
+    pub low_battery: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Nexus,
+    Prologue
+}
+
+/// Pulse position modulation: every bit is a short pulse followed by a gap,
+/// length of the gap defines bit value, long gap separates repeated rows.
+pub struct PpmTimings {
+    pub zero_gap_us: u32,
+    pub one_gap_us: u32,
+    pub sync_gap_us: u32
+}
+
+pub trait Decoder : Send + Sync {
+    fn decode(&self, pulses: &[Pulse]) -> Option<WeatherReading>;
+}
+
+impl Protocol {
+    pub fn decoder(&self) -> Box<dyn Decoder> {
+        match self {
+            Protocol::Nexus => Box::new(nexus::Nexus),
+            Protocol::Prologue => Box::new(prologue::Prologue)
+        }
+    }
+}
+
+impl PpmTimings {
+    /// Splits pulses into rows of bits, invalid gaps drop the current row.
+    pub fn rows(&self, pulses: &[Pulse]) -> Vec<Vec<bool>> {
+        let zero_one = (self.zero_gap_us + self.one_gap_us) / 2;
+        let one_sync = (self.one_gap_us + self.sync_gap_us) / 2;
+        let min_gap = self.zero_gap_us / 2;
+
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+
+        for pulse in pulses.iter().filter(|p| !p.high) {
+            let gap = pulse.duration_us;
+            if gap < min_gap {
+                row.clear();
+            } else if gap < zero_one {
+                row.push(false);
+            } else if gap < one_sync {
+                row.push(true);
+            } else if !row.is_empty() {
+                rows.push(std::mem::take(&mut row));
+            }
+        }
+
+        if !row.is_empty() {
+            rows.push(row);
+        }
+
+        rows
+    }
+
+    /// Returns row with expected length which repeated at least twice.
+    pub fn repeated_row(&self, pulses: &[Pulse], bits: usize) -> Option<Vec<bool>> {
+        let rows : Vec<Vec<bool>> = self.rows(pulses)
+            .into_iter()
+            .filter(|r| r.len() == bits)
+            .collect();
+
+        rows.iter()
+            .find(|r| rows.iter().filter(|o| o == r).count() >= 2)
+            .cloned()
+    }
+}
+
+pub fn bits_to_u32(bits: &[bool]) -> u32 {
+    bits.iter().fold(0, |acc, b| (acc << 1) | *b as u32)
+}
+
+/// Converts 12 bit two's complement value in tenths of degree.
+pub fn temperature_from_12_bits(value: u32) -> f32 {
+    let value = if value & 0x800 != 0 { value as i32 - 0x1000 } else { value as i32 };
+    value as f32 / 10.0
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use crate::utils::rf433::{PpmTimings, Pulse};
+
+    const HIGH_US : u32 = 500;
+    /// Receiver output is not exact, every width is shifted by the next value of this pattern.
+    const JITTER_US : [i32; 7] = [-60, 35, 0, 80, -25, 50, -45];
+
+    /// Pulse widths of a frame sent `repeats` times like sensors do, with a sync gap after every row.
+    pub fn ppm_pulses(timings: &PpmTimings, frame: u64, bits: usize, repeats: usize) -> Vec<Pulse> {
+        let mut widths = Vec::new();
+        for _ in 0..repeats {
+            for i in (0..bits).rev() {
+                let gap = if frame >> i & 1 == 1 { timings.one_gap_us } else { timings.zero_gap_us };
+                widths.push((HIGH_US, gap));
+            }
+            widths.push((HIGH_US, timings.sync_gap_us));
+        }
+
+        let mut jitter = JITTER_US.iter().cycle();
+        let mut shift = |width: u32| (width as i32 + jitter.next().unwrap()) as u32;
+
+        widths.into_iter()
+            .flat_map(|(high, gap)| [
+                Pulse { high: true, duration_us: shift(high) },
+                Pulse { high: false, duration_us: shift(gap) }
+            ])
+            .collect()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+use crate::utils::rf433::{bits_to_u32, temperature_from_12_bits, Decoder, PpmTimings, Pulse, WeatherReading};
+
+const BITS : usize = 36;
+const TIMINGS : PpmTimings = PpmTimings {
+    zero_gap_us: 1000,
+    one_gap_us: 2000,
+    sync_gap_us: 4000
+};
+
+/// Nexus compatible sensors, 36 bits:
+/// `id:8 battery:1 zero:1 channel:2 temperature:12 const(0xf):4 humidity:8`.
+pub struct Nexus;
+
+impl Decoder for Nexus {
+    fn decode(&self, pulses: &[Pulse]) -> Option<WeatherReading> {
+        let row = TIMINGS.repeated_row(pulses, BITS)?;
+
+        if row[9] || bits_to_u32(&row[24..28]) != 0xf {
+            return None;
+        }
+
+        let humidity = bits_to_u32(&row[28..36]) as i32;
+        if humidity > 100 {
+            return None;
+        }
+
+        Some(WeatherReading {
+            id: bits_to_u32(&row[0..8]) as u8,
+            low_battery: !row[8],
+            channel: bits_to_u32(&row[10..12]) as i32 + 1,
+            temperature: temperature_from_12_bits(bits_to_u32(&row[12..24])),
+            humidity: Some(humidity)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rf433::fixtures::ppm_pulses;
+
+    #[test]
+    fn decodes_positive_temperature() {
+        // id 0xa5, battery ok, channel 2, 23.4°C, 56%
+        let pulses = ppm_pulses(&TIMINGS, 0xa590eaf38, BITS, 3);
+
+        assert_eq!(Nexus.decode(&pulses), Some(WeatherReading {
+            id: 0xa5,
+            channel: 2,
+            temperature: 23.4,
+            humidity: Some(56),
+            low_battery: false
+        }));
+    }
+
+    #[test]
+    fn decodes_negative_temperature_and_low_battery() {
+        // id 0x3c, battery low, channel 3, -5.2°C, 81%
+        let pulses = ppm_pulses(&TIMINGS, 0x3c2fccf51, BITS, 3);
+
+        assert_eq!(Nexus.decode(&pulses), Some(WeatherReading {
+            id: 0x3c,
+            channel: 3,
+            temperature: -5.2,
+            humidity: Some(81),
+            low_battery: true
+        }));
+    }
+
+    #[test]
+    fn rejects_bad_check_nibble() {
+        let pulses = ppm_pulses(&TIMINGS, 0xa590eae38, BITS, 3);
+        assert_eq!(Nexus.decode(&pulses), None);
+    }
+
+    #[test]
+    fn rejects_humidity_out_of_range() {
+        let pulses = ppm_pulses(&TIMINGS, 0xa590eaf00 | 120, BITS, 3);
+        assert_eq!(Nexus.decode(&pulses), None);
+    }
+
+    #[test]
+    fn rejects_not_repeated_row() {
+        let mut pulses = ppm_pulses(&TIMINGS, 0xa590eaf38, BITS, 1);
+        pulses.extend(ppm_pulses(&TIMINGS, 0xa590eaf39, BITS, 1));
+
+        assert_eq!(Nexus.decode(&pulses), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+use crate::utils::rf433::{bits_to_u32, temperature_from_12_bits, Decoder, PpmTimings, Pulse, WeatherReading};
+
+const BITS : usize = 36;
+/// Humidity byte of sensors without humidity element.
+const NO_HUMIDITY : u32 = 0xcc;
+const TIMINGS : PpmTimings = PpmTimings {
+    zero_gap_us: 2000,
+    one_gap_us: 4000,
+    sync_gap_us: 9000
+};
+
+/// Prologue compatible sensors, 36 bits:
+/// `type(0x5 or 0x9):4 id:8 battery:1 button:1 channel:2 temperature:12 humidity:8`,
+/// temperature only sensors send `0xcc` as humidity.
+pub struct Prologue;
+
+impl Decoder for Prologue {
+    fn decode(&self, pulses: &[Pulse]) -> Option<WeatherReading> {
+        let row = TIMINGS.repeated_row(pulses, BITS)?;
+
+        let kind = bits_to_u32(&row[0..4]);
+        if kind != 0x5 && kind != 0x9 {
+            return None;
+        }
+
+        let humidity = match bits_to_u32(&row[28..36]) {
+            NO_HUMIDITY => None,
+            h if h > 100 => return None,
+            h => Some(h as i32)
+        };
+
+        Some(WeatherReading {
+            id: bits_to_u32(&row[4..12]) as u8,
+            low_battery: !row[12],
+            channel: bits_to_u32(&row[14..16]) as i32 + 1,
+            temperature: temperature_from_12_bits(bits_to_u32(&row[16..28])),
+            humidity
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rf433::fixtures::ppm_pulses;
+
+    #[test]
+    fn decodes_positive_temperature() {
+        // type 0x9, id 0x3c, battery ok, channel 3, 21.7°C, 48%
+        let pulses = ppm_pulses(&TIMINGS, 0x93ca0d930, BITS, 4);
+
+        assert_eq!(Prologue.decode(&pulses), Some(WeatherReading {
+            id: 0x3c,
+            channel: 3,
+            temperature: 21.7,
+            humidity: Some(48),
+            low_battery: false
+        }));
+    }
+
+    #[test]
+    fn decodes_negative_temperature_and_low_battery() {
+        // type 0x5, id 0x12, battery low, channel 1, -12.5°C, 97%
+        let pulses = ppm_pulses(&TIMINGS, 0x5120f8361, BITS, 4);
+
+        assert_eq!(Prologue.decode(&pulses), Some(WeatherReading {
+            id: 0x12,
+            channel: 1,
+            temperature: -12.5,
+            humidity: Some(97),
+            low_battery: true
+        }));
+    }
+
+    #[test]
+    fn rejects_unknown_type() {
+        let pulses = ppm_pulses(&TIMINGS, 0x33ca0d930, BITS, 4);
+        assert_eq!(Prologue.decode(&pulses), None);
+    }
+
+    #[test]
+    fn decodes_sensor_without_humidity() {
+        // type 0x9, id 0x3c, battery ok, channel 3, 21.7°C, no humidity element
+        let pulses = ppm_pulses(&TIMINGS, 0x93ca0d9cc, BITS, 4);
+
+        assert_eq!(Prologue.decode(&pulses).map(|r| (r.temperature, r.humidity)), Some((21.7, None)));
+    }
+
+    #[test]
+    fn rejects_humidity_out_of_range() {
+        let pulses = ppm_pulses(&TIMINGS, 0x93ca0d9c8, BITS, 4);
+        assert_eq!(Prologue.decode(&pulses), None);
+    }
+
+    /// Gaps after every bit of `0x9b4a0e6cc` followed by the sync gap, written out by hand
+    /// from the rtl_433 Prologue description (500us pulse, 2000/4000us gaps, 9000us sync)
+    /// instead of the decoder timings, this is not a capture from a sensor.
+    const TEMPERATURE_ONLY_GAPS : [u32; 37] = [
+        3920, 2070, 1950, 4110,  4030, 1890, 3980, 4060,  2040, 3890, 1960, 2110,
+        4080, 1930, 4020, 2050,  1980, 2090, 1940, 2010,  4090, 3950, 4040, 2020,
+        1910, 3970, 4100, 1990,  3940, 4070, 2030, 1920,  4010, 3960, 2080, 1970,
+        9150
+    ];
+
+    #[test]
+    fn decodes_frame_from_protocol_description() {
+        let pulses : Vec<Pulse> = (0..7)
+            .flat_map(|_| TEMPERATURE_ONLY_GAPS.iter())
+            .enumerate()
+            .flat_map(|(i, gap)| [
+                Pulse { high: true, duration_us: 470 + (i as u32 * 37) % 90 },
+                Pulse { high: false, duration_us: *gap }
+            ])
+            .collect();
+
+        // type 0x9, id 0xb4, battery ok, channel 3, 23.0°C
+        assert_eq!(Prologue.decode(&pulses), Some(WeatherReading {
+            id: 0xb4,
+            channel: 3,
+            temperature: 23.0,
+            humidity: None,
+            low_battery: false
+        }));
+    }
+
+    #[test]
+    fn rejects_not_repeated_row() {
+        let mut pulses = ppm_pulses(&TIMINGS, 0x93ca0d930, BITS, 1);
+        pulses.extend(ppm_pulses(&TIMINGS, 0x93ca0d931, BITS, 1));
+
+        assert_eq!(Prologue.decode(&pulses), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,90 @@
+#[cfg(target_os = "linux")]
+use rppal::gpio::{Gpio, InputPin, Level, Trigger};
+#[cfg(target_os = "linux")]
+use std::sync::mpsc::{self, RecvTimeoutError};
+#[cfg(target_os = "linux")]
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+use crate::utils::rf433::{Decoder, Pulse};
+use crate::utils::rf433::{Protocol, WeatherReading};
+use crate::utils::rppal_error::RppalError;
+
+#[cfg(target_os = "linux")]
+const BURST_GAP_US : u32 = 20_000;
+#[cfg(target_os = "linux")]
+const BURST_TIMEOUT : Duration = Duration::from_millis(50);
+#[cfg(target_os = "linux")]
+const MAX_BURST_PULSES : usize = 4096;
+
+/// Captures edges from the 433 MHz receiver data pin and decodes them on a separate thread.
+pub struct Receiver {
+    #[cfg(target_os = "linux")]
+    _pin: InputPin
+}
+
+impl Receiver {
+    #[cfg(target_os = "windows")]
+    pub fn start<F>(_: u8, _: &[Protocol], _: F) -> Result<Self, RppalError>
+        where F: Fn(WeatherReading) + Send + 'static {
+        Ok(Receiver {
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn start<F>(pin: u8, protocols: &[Protocol], on_reading: F) -> Result<Self, RppalError>
+        where F: Fn(WeatherReading) + Send + 'static {
+        let mut pin = Gpio::new()?
+            .get(pin)?
+            .into_input();
+
+        let (sender, receiver) = mpsc::channel::<Pulse>();
+        let mut last_edge = Instant::now();
+
+        pin.set_async_interrupt(Trigger::Both, move |level| {
+            let now = Instant::now();
+            let duration_us = now.duration_since(last_edge).as_micros().min(u32::MAX as u128) as u32;
+            last_edge = now;
+
+            // Level after the edge, so the finished pulse had the opposite one.
+            let _ = sender.send(Pulse {
+                high: level == Level::Low,
+                duration_us
+            });
+        })?;
+
+        let decoders : Vec<Box<dyn Decoder>> = protocols.iter().map(|p| p.decoder()).collect();
+        thread::spawn(move || {
+            let mut burst = Vec::new();
+            loop {
+                match receiver.recv_timeout(BURST_TIMEOUT) {
+                    Ok(pulse) => {
+                        let is_burst_end = !pulse.high && pulse.duration_us > BURST_GAP_US;
+                        burst.push(pulse);
+                        if !is_burst_end && burst.len() < MAX_BURST_PULSES {
+                            continue;
+                        }
+                    },
+                    Err(RecvTimeoutError::Timeout) => {},
+                    Err(RecvTimeoutError::Disconnected) => break
+                }
+
+                if burst.is_empty() {
+                    continue;
+                }
+
+                if let Some(reading) = decoders.iter().find_map(|d| d.decode(&burst)) {
+                    on_reading(reading);
+                }
+
+                burst.clear();
+            }
+        });
+
+        Ok(Receiver {
+            _pin: pin
+        })
+    }
+}
\ No newline at end of file
@@ -1,7 +1,11 @@
 #[cfg(target_os = "linux")]
 use rppal::gpio;
 #[cfg(target_os = "linux")]
+use rppal::i2c;
+#[cfg(target_os = "linux")]
 use rppal::pwm;
+#[cfg(target_os = "linux")]
+use rppal::spi;
 
 use thiserror::Error;
 
@@ -12,5 +16,11 @@ pub enum RppalError {
     Gpio(#[from] gpio::Error),
     #[cfg(target_os = "linux")]
     #[error("Pwm error: {0}")]
-    Pwm(#[from] pwm::Error)
+    Pwm(#[from] pwm::Error),
+    #[cfg(target_os = "linux")]
+    #[error("I2c error: {0}")]
+    I2c(#[from] i2c::Error),
+    #[cfg(target_os = "linux")]
+    #[error("Spi error: {0}")]
+    Spi(#[from] spi::Error)
 }
\ No newline at end of file
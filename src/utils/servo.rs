@@ -1,56 +1,205 @@
 #[cfg(target_os = "linux")]
-use rppal::*;
+use rppal::gpio::{Gpio, OutputPin};
 #[cfg(target_os = "linux")]
 use rppal::pwm::{Channel, Pwm};
+use serde::{Deserialize, Serialize};
 use crate::utils::rppal_error::RppalError;
 #[cfg(target_os = "linux")]
 use std::time::Duration;
 
 #[cfg(target_os = "linux")]
+const PERIOD : Duration = Duration::from_millis(20);
+
 const DUTY_CYCLE_START : f64 = 0.03;
-#[cfg(target_os = "linux")]
 const DUTY_CYCLE_ZERO : f64 = 0.08;
-#[cfg(target_os = "linux")]
 const DUTY_CYCLE_LENGTH : f64 = 0.1;
+pub const DEGREE_START : f32 = -90.0;
+pub const DEGREE_END : f32 = 90.0;
+
+/// Duty cycles of the servo: `duty_cycle_start` at -90, `duty_cycle_zero` at 0
+/// and `duty_cycle_start + duty_cycle_length` at 90 degrees.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ServoCalibration {
+    pub duty_cycle_start: f64,
+    pub duty_cycle_zero: f64,
+    pub duty_cycle_length: f64
+}
+
+/// Hardware PWM channel or any GPIO pin with software PWM, which is less precise and can jitter.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ServoPin {
+    Pwm0,
+    Pwm1,
+    Gpio { pin: u8 }
+}
+
 #[cfg(target_os = "linux")]
-const DEGREE_START : f32 = -90.0;
-#[cfg(target_os = "linux")]
-const DEGREE_END : f32 = 90.0;
+enum Output {
+    Hardware(Pwm),
+    Software(OutputPin)
+}
 
 pub struct Servo {
     #[cfg(target_os = "linux")]
-    pwm: Pwm
+    output: Output,
+    calibration: ServoCalibration
+}
+
+impl Default for ServoCalibration {
+    fn default() -> Self {
+        ServoCalibration {
+            duty_cycle_start: DUTY_CYCLE_START,
+            duty_cycle_zero: DUTY_CYCLE_ZERO,
+            duty_cycle_length: DUTY_CYCLE_LENGTH
+        }
+    }
+}
+
+impl ServoCalibration {
+    pub fn is_valid(&self) -> bool {
+        let end = self.duty_cycle_start + self.duty_cycle_length;
+        self.duty_cycle_start > 0.0 && self.duty_cycle_length > 0.0 && end < 1.0
+            && self.duty_cycle_zero > self.duty_cycle_start && self.duty_cycle_zero < end
+    }
+
+    /// Angles below zero are mapped to `start..zero` and above zero to `zero..end`,
+    /// so the zero duty cycle is exactly the middle position.
+    fn duty_cycle(&self, angle: f32) -> f64 {
+        let angle = angle.clamp(DEGREE_START, DEGREE_END) as f64;
+        let end = self.duty_cycle_start + self.duty_cycle_length;
+        if angle < 0.0 {
+            self.duty_cycle_zero + (self.duty_cycle_zero - self.duty_cycle_start) * angle / 90.0
+        } else {
+            self.duty_cycle_zero + (end - self.duty_cycle_zero) * angle / 90.0
+        }
+    }
 }
 
 impl Servo {
     #[cfg(target_os = "windows")]
-    pub fn new() -> Result<Servo, RppalError> {
+    pub fn new(_pin: ServoPin, calibration: ServoCalibration, _angle: f32) -> Result<Servo, RppalError> {
         Ok(Servo {
+            calibration
         })
     }
 
     #[cfg(target_os = "linux")]
-    pub fn new() -> Result<Servo, RppalError> {
-        let pwm = pwm::Pwm::new(Channel::Pwm0)?;
-        pwm.set_period(Duration::from_millis(20))?;
-        pwm.set_duty_cycle(DUTY_CYCLE_ZERO)?;
-        pwm.enable()?;
+    pub fn new(pin: ServoPin, calibration: ServoCalibration, angle: f32) -> Result<Servo, RppalError> {
+        let output = match pin {
+            ServoPin::Pwm0 => Output::Hardware(Servo::hardware(Channel::Pwm0)?),
+            ServoPin::Pwm1 => Output::Hardware(Servo::hardware(Channel::Pwm1)?),
+            ServoPin::Gpio { pin } => Output::Software(Gpio::new()?.get(pin)?.into_output())
+        };
 
-        Ok(Servo {
-            pwm
-        })
+        let mut servo = Servo {
+            output,
+            calibration
+        };
+
+        servo.turn_to(angle)?;
+
+        Ok(servo)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn hardware(channel: Channel) -> Result<Pwm, RppalError> {
+        let pwm = Pwm::new(channel)?;
+        pwm.set_period(PERIOD)?;
+        Ok(pwm)
+    }
+
+    pub fn set_calibration(&mut self, calibration: ServoCalibration) {
+        self.calibration = calibration;
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn turn_to(&mut self, angle: f32) -> Result<(), RppalError> {
+        let _ = self.calibration.duty_cycle(angle);
+        Ok(())
+    }
+
+    /// Enables the PWM output, if it was disabled.
+    #[cfg(target_os = "linux")]
+    pub fn turn_to(&mut self, angle: f32) -> Result<(), RppalError> {
+        let duty_cycle = self.calibration.duty_cycle(angle);
+        match &mut self.output {
+            Output::Hardware(pwm) => {
+                pwm.set_duty_cycle(duty_cycle)?;
+                pwm.enable()?;
+            },
+            Output::Software(pin) => pin.set_pwm(PERIOD, PERIOD.mul_f64(duty_cycle))?
+        }
+        Ok(())
     }
 
     #[cfg(target_os = "windows")]
-    pub fn turn_to(&self, _: f32) -> Result<(), RppalError> {
+    pub fn disable(&mut self) -> Result<(), RppalError> {
         Ok(())
     }
 
+    /// Stops the PWM output, the servo is not powered to hold its position until the next turn.
     #[cfg(target_os = "linux")]
-    pub fn turn_to(&self, angle: f32) -> Result<(), RppalError> {
-        let corrected_angle = (angle.clamp(DEGREE_START, DEGREE_END) + 90.0) as f64;
-        let duty_cycle = DUTY_CYCLE_START + corrected_angle * DUTY_CYCLE_LENGTH / 180.0;
-        self.pwm.set_duty_cycle(duty_cycle)?;
+    pub fn disable(&mut self) -> Result<(), RppalError> {
+        match &mut self.output {
+            Output::Hardware(pwm) => pwm.disable()?,
+            Output::Software(pin) => {
+                pin.clear_pwm()?;
+                pin.set_low();
+            }
+        }
         Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 1e-9, "{}, expected {}", value, expected);
+    }
+
+    fn calibration(start: f64, zero: f64, length: f64) -> ServoCalibration {
+        ServoCalibration {
+            duty_cycle_start: start,
+            duty_cycle_zero: zero,
+            duty_cycle_length: length
+        }
+    }
+
+    #[test]
+    fn maps_angles_with_default_calibration() {
+        let calibration = ServoCalibration::default();
+
+        assert_near(calibration.duty_cycle(-90.0), 0.03);
+        assert_near(calibration.duty_cycle(-45.0), 0.055);
+        assert_near(calibration.duty_cycle(0.0), 0.08);
+        assert_near(calibration.duty_cycle(45.0), 0.105);
+        assert_near(calibration.duty_cycle(90.0), 0.13);
+        assert_near(calibration.duty_cycle(120.0), 0.13);
+        assert_near(calibration.duty_cycle(-120.0), 0.03);
+    }
+
+    #[test]
+    fn keeps_zero_in_the_middle_of_asymmetric_calibration() {
+        let calibration = calibration(0.02, 0.06, 0.1);
+
+        assert_near(calibration.duty_cycle(-90.0), 0.02);
+        assert_near(calibration.duty_cycle(-45.0), 0.04);
+        assert_near(calibration.duty_cycle(0.0), 0.06);
+        assert_near(calibration.duty_cycle(45.0), 0.09);
+        assert_near(calibration.duty_cycle(90.0), 0.12);
+    }
+
+    #[test]
+    fn validates_calibration() {
+        assert!(ServoCalibration::default().is_valid());
+        assert!(!calibration(0.0, 0.08, 0.1).is_valid());
+        assert!(!calibration(0.03, 0.08, 0.0).is_valid());
+        assert!(!calibration(0.5, 0.8, 0.5).is_valid());
+        assert!(!calibration(0.03, 0.03, 0.1).is_valid());
+        assert!(!calibration(0.03, 0.13, 0.1).is_valid());
+        assert!(!calibration(0.03, 0.02, 0.1).is_valid());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::server::server_error::ServerError;
+
+/// Persistent state kept as json files in one directory.
+pub struct Storage {
+    path: PathBuf
+}
+
+impl Storage {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        fs::create_dir_all(&path)?;
+        Ok(Storage {
+            path: path.as_ref().to_path_buf()
+        })
+    }
+
+    pub fn load<T: DeserializeOwned + Default>(&self, name: &str) -> Result<T, ServerError> {
+        let file_path = self.file_path(name);
+        if !file_path.exists() {
+            return Ok(Default::default());
+        }
+
+        let data = fs::read(&file_path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), ServerError> {
+        let data = serde_json::to_vec_pretty(value)?;
+
+        let temp_path = self.path.join(format!("{}.json.tmp", name));
+        fs::write(&temp_path, data)?;
+        fs::rename(&temp_path, self.file_path(name))?;
+
+        Ok(())
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.file_path(name).exists()
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), ServerError> {
+        fs::remove_file(self.file_path(name))?;
+        Ok(())
+    }
+
+    /// Writes and removes a probe file, so a read-only or full file system is found before state is lost.
+    pub fn check_writable(&self) -> Result<(), io::Error> {
+        let probe_path = self.path.join(".health.tmp");
+        fs::write(&probe_path, b"ok")?;
+        fs::remove_file(&probe_path)
+    }
+
+    fn file_path(&self, name: &str) -> PathBuf {
+        self.path.join(format!("{}.json", name))
+    }
+}
\ No newline at end of file
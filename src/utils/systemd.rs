@@ -0,0 +1,182 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::net::TcpListener;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::FromRawFd;
+#[cfg(target_os = "linux")]
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use crate::config::Config;
+
+const NOTIFY_SOCKET : &str = "NOTIFY_SOCKET";
+const WATCHDOG_USEC : &str = "WATCHDOG_USEC";
+const WATCHDOG_PID : &str = "WATCHDOG_PID";
+const LISTEN_PID : &str = "LISTEN_PID";
+const LISTEN_FDS : &str = "LISTEN_FDS";
+#[cfg(target_os = "linux")]
+const LISTEN_FDS_START : i32 = 3;
+const RECOMMENDED_WATCHDOG_SECONDS : u64 = 30;
+
+/// Sends `sd_notify` state, like `READY=1` or `STATUS=...`, does nothing if not started by systemd.
+#[cfg(target_os = "linux")]
+pub fn notify(state: &str) -> Result<(), io::Error> {
+    let path = match env::var(NOTIFY_SOCKET) {
+        Ok(p) => p,
+        Err(_) => return Ok(())
+    };
+
+    let socket = UnixDatagram::unbound()?;
+    match path.strip_prefix('@') {
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+        },
+        None => {
+            socket.send_to(state.as_bytes(), path)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn notify(_: &str) -> Result<(), io::Error> {
+    Ok(())
+}
+
+/// Returns how often `WATCHDOG=1` should be sent, it is a half of `WatchdogSec` from the unit.
+pub fn watchdog_interval() -> Option<Duration> {
+    parse_watchdog_interval(env::var(WATCHDOG_PID).ok().as_deref(), env::var(WATCHDOG_USEC).ok().as_deref(), process::id())
+}
+
+/// Watchdog is disabled if `WATCHDOG_USEC` is zero.
+fn parse_watchdog_interval(pid: Option<&str>, usec: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if !is_own(pid, own_pid) {
+        return None;
+    }
+
+    let usec : u64 = usec?.parse().ok()?;
+    if usec == 0 {
+        return None;
+    }
+
+    Some(Duration::from_micros(usec) / 2)
+}
+
+/// Takes the listening socket passed by systemd socket activation.
+#[cfg(target_os = "linux")]
+pub fn listener() -> Result<Option<TcpListener>, io::Error> {
+    let count = match parse_listen_fds(env::var(LISTEN_PID).ok().as_deref(), env::var(LISTEN_FDS).ok().as_deref(), process::id()) {
+        Some(c) => c,
+        None => return Ok(None)
+    };
+
+    // child processes should not take sockets
+    env::remove_var(LISTEN_PID);
+    env::remove_var(LISTEN_FDS);
+
+    if count != 1 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("expected one activation socket, got {}", count)));
+    }
+
+    // systemd passes sockets starting from fd 3, it is owned by this process now
+    let listener = unsafe { TcpListener::from_raw_fd(LISTEN_FDS_START) };
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+#[cfg(target_os = "windows")]
+pub fn listener() -> Result<Option<TcpListener>, io::Error> {
+    Ok(None)
+}
+
+/// Recommended service unit for the config, it expects the server to be started from the current binary.
+pub fn unit_file(config: &Config, config_path: &str) -> Result<String, io::Error> {
+    let exe = env::current_exe()?;
+    let config_path = fs::canonicalize(config_path)?;
+    let working_dir = config_path.parent().unwrap_or_else(|| Path::new("/"));
+
+    Ok(format!("\
+[Unit]
+Description=Plants care server
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=notify
+NotifyAccess=main
+ExecStart={exe} serve --config {config}
+WorkingDirectory={dir}
+Restart=on-failure
+WatchdogSec={watchdog}
+TimeoutStopSec={stop}
+
+[Install]
+WantedBy=multi-user.target
+
+# Optional socket activation, save as rpi_home.socket and enable it instead of the service:
+# [Socket]
+# ListenStream={address}
+#
+# [Install]
+# WantedBy=sockets.target
+",
+        exe = exe.display(),
+        config = config_path.display(),
+        dir = working_dir.display(),
+        watchdog = RECOMMENDED_WATCHDOG_SECONDS,
+        stop = config.drain_timeout_seconds + 10,
+        address = &config.address))
+}
+
+/// Number of passed sockets, unlike the watchdog `LISTEN_PID` is required.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_listen_fds(pid: Option<&str>, fds: Option<&str>, own_pid: u32) -> Option<i32> {
+    if pid.is_none() || !is_own(pid, own_pid) {
+        return None;
+    }
+
+    fds?.parse().ok()
+}
+
+/// Variables are meant for this process if pid is not set or equal to ours.
+fn is_own(pid: Option<&str>, own_pid: u32) -> bool {
+    match pid {
+        Some(pid) => pid.parse::<u32>().map(|p| p == own_pid).unwrap_or(false),
+        None => true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halves_watchdog_interval() {
+        assert_eq!(parse_watchdog_interval(None, Some("30000000"), 42), Some(Duration::from_secs(15)));
+        assert_eq!(parse_watchdog_interval(Some("42"), Some("5000001"), 42), Some(Duration::from_nanos(2_500_000_500)));
+        assert_eq!(parse_watchdog_interval(None, Some("0"), 42), None);
+        assert_eq!(parse_watchdog_interval(None, Some("30s"), 42), None);
+        assert_eq!(parse_watchdog_interval(None, None, 42), None);
+    }
+
+    #[test]
+    fn ignores_watchdog_of_other_process() {
+        assert_eq!(parse_watchdog_interval(Some("41"), Some("30000000"), 42), None);
+        assert_eq!(parse_watchdog_interval(Some("pid"), Some("30000000"), 42), None);
+    }
+
+    #[test]
+    fn takes_sockets_only_of_this_process() {
+        assert_eq!(parse_listen_fds(Some("42"), Some("1"), 42), Some(1));
+        assert_eq!(parse_listen_fds(Some("42"), Some("2"), 42), Some(2));
+        assert_eq!(parse_listen_fds(Some("41"), Some("1"), 42), None);
+        assert_eq!(parse_listen_fds(None, Some("1"), 42), None);
+        assert_eq!(parse_listen_fds(Some("42"), None, 42), None);
+    }
+}
\ No newline at end of file
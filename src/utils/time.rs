@@ -0,0 +1,14 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SECONDS_IN_DAY : u64 = 24 * 60 * 60;
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn unix_day(time: u64) -> u64 {
+    time / SECONDS_IN_DAY
+}
\ No newline at end of file
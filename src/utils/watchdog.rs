@@ -0,0 +1,32 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Linux watchdog device, it is armed on open and reboots the system
+/// if nothing is written during the watchdog timeout.
+pub struct Watchdog {
+    file: File
+}
+
+impl Watchdog {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .open(path)?;
+
+        Ok(Watchdog {
+            file
+        })
+    }
+
+    pub fn keep_alive(&mut self) -> Result<(), io::Error> {
+        self.file.write_all(b"\0")?;
+        self.file.flush()
+    }
+
+    /// Writes the magic close character, so the watchdog is disarmed when the device is closed.
+    pub fn disarm(mut self) -> Result<(), io::Error> {
+        self.file.write_all(b"V")?;
+        self.file.flush()
+    }
+}
\ No newline at end of file
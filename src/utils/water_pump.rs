@@ -1,54 +1,95 @@
 #[cfg(target_os = "linux")]
-use rppal::gpio::Gpio;
-#[cfg(target_os = "linux")]
+use rppal::gpio::{Gpio, OutputPin};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::utils::rppal_error::RppalError;
 
-#[cfg(target_os = "linux")]
-const WATER_PUMP_POWER_PIN : u8 = 5;
-
 pub struct WaterPump {
+    state: Mutex<State>
+}
+
+struct State {
     #[cfg(target_os = "linux")]
-    gpio: Gpio
+    pin: OutputPin,
+    started: Option<Instant>
 }
 
 impl WaterPump {
     #[cfg(target_os = "windows")]
-    pub fn new() -> Result<Self, RppalError> {
+    pub fn new(_: u8) -> Result<Self, RppalError> {
         Ok(WaterPump {
+            state: Mutex::new(State {
+                started: None
+            })
         })
     }
 
     #[cfg(target_os = "linux")]
-    pub fn new() -> Result<Self, RppalError> {
-        let gpio = Gpio::new()?;
-
-        let mut power_pin = gpio.get(WATER_PUMP_POWER_PIN)?
+    pub fn new(power_pin: u8) -> Result<Self, RppalError> {
+        let mut pin = Gpio::new()?
+            .get(power_pin)?
             .into_output();
 
-        power_pin.set_low();
+        pin.set_low();
 
         Ok(WaterPump {
-            gpio
+            state: Mutex::new(State {
+                pin,
+                started: None
+            })
         })
     }
 
+    /// Checks that GPIO is still accessible, pins of created pumps are already initialized.
     #[cfg(target_os = "windows")]
-    pub fn enable(&self, _: Duration) -> Result<(), RppalError> {
+    pub fn check_gpio() -> Result<(), RppalError> {
         Ok(())
     }
 
     #[cfg(target_os = "linux")]
-    pub fn enable(&self, time: Duration) -> Result<(), RppalError> {
-        let mut power_pin = self.gpio.get(WATER_PUMP_POWER_PIN)?
-            .into_output();
+    pub fn check_gpio() -> Result<(), RppalError> {
+        Gpio::new()?;
+        Ok(())
+    }
 
-        power_pin.set_high();
+    /// Pin is not locked while the pump runs, so `disable` can stop it from another thread.
+    pub fn enable(&self, time: Duration) -> Result<(), RppalError> {
+        self.set(true);
         thread::sleep(time);
-        power_pin.set_low();
+        self.set(false);
 
         Ok(())
     }
+
+    /// Works with poisoned state too, it is used to stop the pump after a panic.
+    pub fn disable(&self) {
+        self.set(false);
+    }
+
+    /// Returns time since the pump was enabled, `None` if it is disabled.
+    pub fn running_for(&self) -> Option<Duration> {
+        self.state().started.map(|s| s.elapsed())
+    }
+
+    fn set(&self, enabled: bool) {
+        let mut state = self.state();
+
+        #[cfg(target_os = "linux")]
+        if enabled {
+            state.pin.set_high();
+        } else {
+            state.pin.set_low();
+        }
+
+        state.started = match enabled {
+            true => Some(Instant::now()),
+            false => None
+        };
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
\ No newline at end of file
@@ -9,33 +9,33 @@ use crate::utils::rppal_error::RppalError;
 
 pub struct WaterSensor {
     #[cfg(target_os = "linux")]
-    gpio: Gpio
+    gpio: Gpio,
+    #[cfg(target_os = "linux")]
+    power_pin: u8,
+    #[cfg(target_os = "linux")]
+    in_pin: u8
 }
 
-#[cfg(target_os = "linux")]
-const WATER_SENSOR_POWER_PIN : u8 = 24;
-
-#[cfg(target_os = "linux")]
-const WATER_SENSOR_IN : u8 = 23;
-
 impl WaterSensor {
     #[cfg(target_os = "windows")]
-    pub fn new() -> Result<Self, RppalError> {
+    pub fn new(_: u8, _: u8) -> Result<Self, RppalError> {
         Ok(WaterSensor {
         })
     }
 
     #[cfg(target_os = "linux")]
-    pub fn new() -> Result<Self, RppalError>{
+    pub fn new(power_pin: u8, in_pin: u8) -> Result<Self, RppalError>{
         let gpio = Gpio::new()?;
 
-        let mut power_pin = gpio.get(WATER_SENSOR_POWER_PIN)?
+        let mut pin = gpio.get(power_pin)?
             .into_output();
 
-        power_pin.set_low();
+        pin.set_low();
 
         Ok(WaterSensor {
-            gpio
+            gpio,
+            power_pin,
+            in_pin
         })
     }
 
@@ -46,10 +46,10 @@ impl WaterSensor {
 
     #[cfg(target_os = "linux")]
     pub fn is_enough(&self) -> Result<bool, RppalError> {
-        let mut power_pin = self.gpio.get(WATER_SENSOR_POWER_PIN)?
+        let mut power_pin = self.gpio.get(self.power_pin)?
             .into_output();
 
-        let in_pin = self.gpio.get(WATER_SENSOR_IN)?
+        let in_pin = self.gpio.get(self.in_pin)?
             .into_input();
 
         power_pin.set_high();